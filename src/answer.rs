@@ -0,0 +1,80 @@
+use serde::Serialize;
+
+/// The answer to one part of a puzzle.
+///
+/// Integers are kept as `i128` so that every primitive integer type a day
+/// computes with converts losslessly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// A picture drawn in characters, one string per row, such as the
+    /// letters on d10's CRT.
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Build a grid answer from text with one row per line.
+    pub fn grid(text: &str) -> Self {
+        Answer::Grid(text.lines().map(str::to_string).collect())
+    }
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! int_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+int_answer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::from(-3).to_string(), "-3");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        assert_eq!(Answer::grid("#.\n.#\n").to_string(), "#.\n.#");
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(serde_json::to_string(&Answer::from(42u8)).unwrap(), "42");
+        assert_eq!(
+            serde_json::to_string(&Answer::grid("#.\n.#")).unwrap(),
+            r##"["#.",".#"]"##
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Min, median and max of a set of timing samples, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Timing {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+}
+
+impl Timing {
+    /// Summarize `samples`, which must not be empty.  For an even number of
+    /// samples the median is the upper of the two middle values.
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        samples.sort();
+        let ns = |d: Duration| d.as_nanos() as u64;
+        Self {
+            min_ns: ns(samples[0]),
+            median_ns: ns(samples[samples.len() / 2]),
+            max_ns: ns(samples[samples.len() - 1]),
+        }
+    }
+}
+
+/// Timings for one part: reading its input, parsing it and solving from
+/// the parsed form are measured separately.
+#[derive(Debug, Serialize)]
+pub struct BenchResult {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub iterations: u32,
+    pub read_time: Timing,
+    pub parse_time: Timing,
+    pub solve_time: Timing,
+}
+
+fn fmt_ns(ns: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(ns))
+}
+
+pub fn print_table(results: &[BenchResult]) {
+    print!("{:>3} {:>4}", "day", "part");
+    for phase in ["read", "parse", "solve"] {
+        print!(
+            "   {:>10} {:>10} {:>10}",
+            format!("{phase} min"),
+            "median",
+            "max"
+        );
+    }
+    println!();
+    for r in results {
+        print!("{:>3} {:>4}", r.day, r.part);
+        for t in [r.read_time, r.parse_time, r.solve_time] {
+            print!(
+                "   {:>10} {:>10} {:>10}",
+                fmt_ns(t.min_ns),
+                fmt_ns(t.median_ns),
+                fmt_ns(t.max_ns)
+            );
+        }
+        println!();
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn to_csv(results: &[BenchResult]) -> String {
+    let mut out = String::from(
+        "day,part,input,iterations,read_min_ns,read_median_ns,read_max_ns,\
+         parse_min_ns,parse_median_ns,parse_max_ns,solve_min_ns,solve_median_ns,solve_max_ns\n",
+    );
+    for r in results {
+        out.push_str(&format!(
+            "{},{},{},{}",
+            r.day,
+            r.part,
+            csv_field(&r.input),
+            r.iterations
+        ));
+        for t in [r.read_time, r.parse_time, r.solve_time] {
+            out.push_str(&format!(",{},{},{}", t.min_ns, t.median_ns, t.max_ns));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// File formats the results can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+}
+
+impl OutputFormat {
+    /// Pick the format from the extension of `path`.
+    pub fn for_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Ok(OutputFormat::Csv),
+            Some("json") => Ok(OutputFormat::Json),
+            _ => bail!(
+                "don't know how to write {}; use a .csv or .json file",
+                path.display()
+            ),
+        }
+    }
+}
+
+pub fn write(path: &Path, format: OutputFormat, results: &[BenchResult]) -> Result<()> {
+    let text = match format {
+        OutputFormat::Csv => to_csv(results),
+        OutputFormat::Json => serde_json::to_string_pretty(results)? + "\n",
+    };
+    std::fs::write(path, text).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_timing() {
+        let mut samples: Vec<Duration> = [5, 1, 4, 2, 3]
+            .into_iter()
+            .map(Duration::from_nanos)
+            .collect();
+        assert_eq!(
+            Timing::from_samples(&mut samples),
+            Timing {
+                min_ns: 1,
+                median_ns: 3,
+                max_ns: 5
+            }
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let t = Timing {
+            min_ns: 1,
+            median_ns: 2,
+            max_ns: 3,
+        };
+        let results = [BenchResult {
+            day: 4,
+            part: 1,
+            input: "a,b.txt".to_string(),
+            iterations: 3,
+            read_time: t,
+            parse_time: t,
+            solve_time: Timing {
+                min_ns: 4,
+                median_ns: 5,
+                max_ns: 6,
+            },
+        }];
+        let csv = to_csv(&results);
+        assert_eq!(
+            csv.lines().next().unwrap().split(',').count(),
+            csv.lines().nth(1).unwrap().split(',').count() - 1
+        );
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("4,1,\"a,b.txt\",3,1,2,3,1,2,3,4,5,6")
+        );
+    }
+}
@@ -1,30 +1,362 @@
-use crate::PuzzleRun;
-use itertools::Itertools;
-
-pub struct D1;
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError};
+use crate::{Answer, Parsed, PuzzleRun};
+use anyhow::{anyhow, Context, Result};
+use rand::Rng as _;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Write;
+use tracing::{event, Level};
 
 pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
-    vec![Box::new(D1)]
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 1,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    crate::lint::each_line(input, |line| match line.text.trim() {
+        "" => Ok(0),
+        _ => line.parse::<u64>(),
+    })
+}
+
+/// `scale` calorie lines, carried by elves of one to eight items each.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let mut out = String::new();
+    let mut left = scale.max(1);
+    while left > 0 {
+        let items = rng.gen_range(1..=8).min(left);
+        for _ in 0..items {
+            writeln!(out, "{}", rng.gen_range(1000..=60000)).unwrap();
+        }
+        left -= items;
+        if left > 0 {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// One elf's load.  Elves are numbered from 1 in input order, as in the
+/// puzzle text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Elf {
+    pub(crate) number: usize,
+    pub(crate) calories: u64,
+}
+
+/// Each elf's calorie total, read a line at a time.  Runs of blank lines
+/// separate elves; a total that overflows `u64` is an error at the line
+/// that overflowed it.
+struct Elves<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    count: usize,
+}
+
+impl<'a> Elves<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            lines: input.lines().enumerate(),
+            count: 0,
+        }
+    }
+}
+
+impl Iterator for Elves<'_> {
+    type Item = Result<Elf, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut calories = None;
+        for (n, text) in self.lines.by_ref() {
+            let line = Line::new(n + 1, text);
+            if text.trim().is_empty() {
+                if calories.is_some() {
+                    break;
+                }
+                continue;
+            }
+            let item = match line.parse::<u64>() {
+                Ok(item) => item,
+                Err(e) => return Some(Err(e)),
+            };
+            match calories.unwrap_or(0u64).checked_add(item) {
+                Some(sum) => calories = Some(sum),
+                None => return Some(Err(line.error("calorie total overflows u64"))),
+            }
+        }
+        let calories = calories?;
+        self.count += 1;
+        Some(Ok(Elf {
+            number: self.count,
+            calories,
+        }))
+    }
+}
+
+/// What the elves carry, gathered in one pass over the input.  Only the top
+/// `k` elves are kept, so memory doesn't grow with the input unless the
+/// distribution is asked for.
+#[derive(Debug)]
+pub(crate) struct Report {
+    /// The `k` elves carrying the most, most first; ties go to the earlier
+    /// elf.
+    pub(crate) top: Vec<Elf>,
+    pub(crate) elves: usize,
+    pub(crate) calories: u64,
+    /// Every elf's total in ascending order, for percentiles.
+    totals: Option<Vec<u64>>,
+}
+
+impl Report {
+    /// Gather the top `k` elves and the overall totals.
+    fn new(input: &str, k: usize) -> Result<Self> {
+        Self::gather(input, k, false)
+    }
+
+    /// As `new`, but also keep every elf's total for `percentile`.
+    fn with_distribution(input: &str, k: usize) -> Result<Self> {
+        Self::gather(input, k, true)
+    }
+
+    fn gather(input: &str, k: usize, distribution: bool) -> Result<Self> {
+        // a min-heap of the best so far; the smallest total, and of equal
+        // totals the latest elf, is the one to drop
+        let mut top = BinaryHeap::with_capacity(k + 1);
+        let mut totals = distribution.then(Vec::new);
+        let mut elves = 0;
+        let mut calories = 0u64;
+        for elf in Elves::new(input) {
+            let elf = elf.context("bad calorie count")?;
+            elves += 1;
+            calories = calories
+                .checked_add(elf.calories)
+                .ok_or_else(|| anyhow!("total calories overflow u64 at elf {}", elf.number))?;
+            if let Some(totals) = totals.as_mut() {
+                totals.push(elf.calories);
+            }
+            top.push(Reverse((elf.calories, Reverse(elf.number))));
+            if top.len() > k {
+                top.pop();
+            }
+        }
+        if let Some(totals) = totals.as_mut() {
+            totals.sort_unstable();
+        }
+        let top = top
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(number)))| Elf { number, calories })
+            .collect();
+        Ok(Self {
+            top,
+            elves,
+            calories,
+            totals,
+        })
+    }
+
+    /// Calories carried by the top elves together.
+    pub(crate) fn top_calories(&self) -> Result<u64> {
+        self.top
+            .iter()
+            .try_fold(0u64, |sum, elf| sum.checked_add(elf.calories))
+            .ok_or_else(|| anyhow!("calories of the top {} elves overflow u64", self.top.len()))
+    }
+
+    pub(crate) fn mean(&self) -> Option<f64> {
+        (self.elves > 0).then(|| self.calories as f64 / self.elves as f64)
+    }
+
+    /// The nearest-rank `p`th percentile of elf totals, if the report kept
+    /// the distribution and there are any elves.
+    pub(crate) fn percentile(&self, p: u8) -> Option<u64> {
+        let totals = self.totals.as_ref().filter(|t| !t.is_empty())?;
+        let rank = (usize::from(p.min(100)) * totals.len())
+            .div_ceil(100)
+            .max(1);
+        Some(totals[rank - 1])
+    }
+
+    pub(crate) fn median(&self) -> Option<u64> {
+        self.percentile(50)
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} elves carry {} calories", self.elves, self.calories)?;
+        if let Some(mean) = self.mean() {
+            write!(f, ", {mean:.1} each on average")?;
+        }
+        for elf in self.top.iter() {
+            write!(f, "\n  elf {}: {}", elf.number, elf.calories)?;
+        }
+        if let Some(median) = self.median() {
+            let p = |p| self.percentile(p).unwrap_or_default();
+            write!(
+                f,
+                "\n  min {}, p10 {}, p25 {}, median {median}, p75 {}, p90 {}, max {}",
+                p(0),
+                p(10),
+                p(25),
+                p(75),
+                p(90),
+                p(100)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The full report on what the elves carry, with the top `k` elves and
+/// the distribution of every elf's total.
+pub(crate) fn report(input: &str, k: usize) -> Result<Report> {
+    Report::with_distribution(input, k)
+}
+
+/// The input is streamed into a report on the top `k` elves as it's read,
+/// so parsing gathers the report and solving only sums the top.
+fn parse(input: &str, k: usize) -> Result<Parsed> {
+    Ok(Parsed::new(Report::new(input, k)?))
 }
-impl PuzzleRun for D1 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+
+/// The calories carried by the top elves.
+fn solve(parsed: Parsed) -> Result<Answer> {
+    let report: Report = parsed.take();
+    event!(Level::DEBUG, "{report}");
+    Ok(report.top_calories()?.into())
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "most calories carried by a single elf"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d1/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        let v = input
-            .lines()
-            .map(|v| v.parse::<u64>().ok())
-            .collect::<Vec<_>>();
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        parse(input, 1)
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        solve(parsed)
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "calories carried by the top three elves"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d1/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        parse(input, 3)
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        solve(parsed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    example_tests!(1: 24000, 45000);
+
+    #[test]
+    fn test_report() {
+        let report = report(include_str!("example.txt"), 3).unwrap();
+        assert_eq!(
+            report.top,
+            [(4, 24000), (3, 11000), (5, 10000)].map(|(number, calories)| Elf { number, calories })
+        );
+        assert_eq!(report.elves, 5);
+        assert_eq!(report.calories, 55000);
+        assert_eq!(report.mean(), Some(11000.0));
+        assert_eq!(report.median(), Some(10000));
+        assert_eq!(report.percentile(0), Some(4000));
+        assert_eq!(report.percentile(100), Some(24000));
+
+        // ties go to the earlier elf, and blank runs are one separator
+        let report = Report::new("5\n\n\n\n3\n2\n\n1\n", 1).unwrap();
+        assert_eq!(
+            report.top,
+            [Elf {
+                number: 1,
+                calories: 5
+            }]
+        );
+        assert_eq!(report.elves, 3);
+        assert_eq!(report.median(), None);
+    }
+
+    #[test]
+    fn test_trailing_blank() {
+        let mut elves = Elves::new("1\n2\n\n3\n\n");
+        let numbers: Vec<usize> = elves.by_ref().map(|elf| elf.unwrap().number).collect();
+        assert_eq!(numbers, [1, 2]);
+        // running out of input doesn't count another elf
+        assert!(elves.next().is_none());
+        assert_eq!(elves.count, 2);
+        assert_eq!(Report::new("1\n2\n\n3\n\n", 1).unwrap().elves, 2);
+    }
+
+    #[test]
+    fn test_top_k() {
+        let input = include_str!("example.txt");
+        let top = |k| report(input, k).unwrap().top_calories().unwrap();
+        assert_eq!(top(0), 0);
+        assert_eq!(top(2), 35000);
+        assert_eq!(top(5), 55000);
+        // asking for more elves than there are gives them all
+        let all = report(input, 9).unwrap();
+        assert_eq!(all.top.len(), 5);
+        assert_eq!(all.top_calories().unwrap(), all.calories);
+        assert_eq!(all.median(), Some(10000));
+    }
 
-        let max: u64 = v
-            .split(|line| line.is_none())
-            .map(|group| group.iter().map(|v| v.unwrap()).sum::<u64>())
-            .sorted()
-            .rev()
-            .take(3)
-            .sum();
+    #[test]
+    fn test_overflow() {
+        let input = format!("{}\n1\n", u64::MAX);
+        let e = Report::new(&input, 1).unwrap_err();
+        assert!(format!("{e:#}").contains("line 2"), "{e:#}");
 
-        format!("max: {max:?}")
+        let input = format!("{}\n\n1\n", u64::MAX);
+        assert!(Report::new(&input, 1).is_err());
+        assert!(Report::new(&input, 0).is_err());
     }
 }
@@ -0,0 +1,196 @@
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError, Token};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::Result;
+use rand::Rng as _;
+use std::fmt::Write;
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 10,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+enum Instr {
+    Noop,
+    Add(i32),
+}
+
+impl Instr {
+    fn decode(line: &Line) -> Result<Self, ScanError> {
+        let tokens: Vec<Token> = line.tokens().collect();
+        match tokens.as_slice() {
+            [op] if op.text == "noop" => Ok(Instr::Noop),
+            [op, addend] if op.text == "addx" => Ok(Instr::Add(addend.parse()?)),
+            [op, ..] if op.text == "noop" || op.text == "addx" => {
+                Err(op.error(format!("wrong number of operands for {}", op.text)))
+            }
+            _ => Err(line.token().error("invalid instruction")),
+        }
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    crate::lint::each_line(input, Instr::decode)
+}
+
+/// `scale` instructions.  Only the first 240 cycles reach the screen.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..scale.max(1) {
+        if rng.gen_bool(0.4) {
+            out.push_str("noop\n");
+        } else {
+            writeln!(out, "addx {}", rng.gen_range(-20..=20)).unwrap();
+        }
+    }
+    out
+}
+
+fn parse(input: &str) -> Result<Vec<Instr>, ScanError> {
+    Scanner::new(input)
+        .lines()
+        .map(|line| Instr::decode(&line))
+        .collect()
+}
+
+fn execute(program: &[Instr]) -> [i32; 240] {
+    let mut cycle_count: usize = 0;
+    let mut register_vals: [i32; 240] = [0; 240];
+    let mut register: i32 = 1;
+
+    for instr in program {
+        if cycle_count >= register_vals.len() {
+            break;
+        }
+        match *instr {
+            // remove this?
+            Instr::Noop => {
+                register_vals[cycle_count] = register;
+                cycle_count += 1;
+            }
+            Instr::Add(val) => {
+                register_vals[cycle_count] = register;
+                cycle_count += 1;
+                if let Some(v) = register_vals.get_mut(cycle_count) {
+                    *v = register;
+                }
+                cycle_count += 1;
+                register += val;
+            }
+        }
+    }
+    register_vals
+}
+
+fn part1(program: &[Instr]) -> i32 {
+    let register_vals = execute(program);
+
+    20 * register_vals[20 - 1]
+        + 60 * register_vals[60 - 1]
+        + 100 * register_vals[100 - 1]
+        + 140 * register_vals[140 - 1]
+        + 180 * register_vals[180 - 1]
+        + 220 * register_vals[220 - 1]
+}
+
+fn part2(program: &[Instr]) -> String {
+    let register_vals = execute(program);
+    let mut screen = String::new();
+    let mut cycle = 0;
+
+    for val in register_vals.iter() {
+        screen.push(if (cycle - val).abs() <= 1 { '#' } else { '.' });
+        if (cycle + 1) % 40 == 0 {
+            screen.push('\n');
+            cycle = 0;
+        } else {
+            cycle += 1;
+        }
+    }
+    screen.trim_end().to_string()
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "sum of the six signal strengths"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d10/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(part1(&parsed.take::<Vec<Instr>>()).into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "image drawn on the CRT"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d10/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(Answer::grid(&part2(&parsed.take::<Vec<Instr>>())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    example_tests!(
+        10: 13140,
+        crate::Answer::grid(
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+        )
+    );
+
+    #[test]
+    fn test_part1() {
+        let data = include_str!("example.txt");
+        assert_eq!(super::part1(&super::parse(data).unwrap()), 13140);
+    }
+}
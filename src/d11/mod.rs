@@ -0,0 +1,362 @@
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError, Token};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::{anyhow, Result};
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::fmt::Write;
+use tracing::{event, Level};
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 11,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+/// The new worry level, or `None` if it doesn't fit in a `u64`.
+type Op = Box<dyn Fn(u64) -> Option<u64>>;
+
+struct M {
+    items: Vec<u64>,
+    op: Op,
+    /// The line of the notes the operation is on, to point at on overflow.
+    op_line: usize,
+    divisor: u64,
+    throw_on_true: usize,
+    throw_on_false: usize,
+    num_inspections: u64,
+}
+
+impl M {
+    fn new(
+        items: Vec<u64>,
+        op: Op,
+        op_line: usize,
+        divisor: u64,
+        throw_on_true: usize,
+        throw_on_false: usize,
+    ) -> Self {
+        Self {
+            items,
+            op,
+            op_line,
+            divisor,
+            throw_on_true,
+            throw_on_false,
+            num_inspections: 0,
+        }
+    }
+
+    /// The worry level of `item` after monkey `id`, this monkey, inspects it.
+    fn inspect(&self, id: usize, item: u64) -> Result<u64> {
+        (self.op)(item).ok_or_else(|| {
+            anyhow!(
+                "line {}: monkey {id}'s operation overflows on worry level {item}",
+                self.op_line
+            )
+        })
+    }
+}
+
+/// The part of `line` after `prefix`, which it must start with.
+fn field<'a>(line: &Line<'a>, prefix: &str) -> Result<Token<'a>, ScanError> {
+    line.token()
+        .strip_prefix(prefix)
+        .ok_or_else(|| line.token().error(format!("expected `{prefix}`")))
+}
+
+fn parse_op(token: &Token) -> Result<Op, ScanError> {
+    let parts: Vec<Token> = Line::from(*token).tokens().collect();
+    let [opcode, operand] = parts.as_slice() else {
+        return Err(token.error("expected an operator and an operand"));
+    };
+    Ok(match (opcode.text, operand.text) {
+        ("+", "old") => Box::new(|x: u64| x.checked_add(x)),
+        ("*", "old") => Box::new(|x: u64| x.checked_mul(x)),
+        ("+", _) => {
+            let operand: u64 = operand.parse()?;
+            Box::new(move |x: u64| x.checked_add(operand))
+        }
+        ("*", _) => {
+            let operand: u64 = operand.parse()?;
+            Box::new(move |x: u64| x.checked_mul(operand))
+        }
+        (op, _) => return Err(opcode.error(format!("unknown operator: {op}"))),
+    })
+}
+
+/// Read monkey `id` from its six lines of notes.
+fn make_monkey(id: usize, input: &[Line]) -> Result<M, ScanError> {
+    let [name, items, op, test, if_true, if_false] = input else {
+        return Err(input[0].error(format!(
+            "expected six lines describing monkey {id}, found {}",
+            input.len()
+        )));
+    };
+    let name = field(name, "Monkey")?;
+    if name.text != format!("{id}:") {
+        return Err(name.error(format!("expected `{id}:`")));
+    }
+    event!(Level::DEBUG, "read monkey {}", id);
+
+    let items = field(items, "Starting items:")?;
+    let items = match items.text {
+        "" => vec![],
+        _ => Line::from(items)
+            .split(",")
+            .iter()
+            .map(|item| item.parse())
+            .collect::<Result<_, _>>()?,
+    };
+    let op_line = op.number;
+    let op = parse_op(&field(op, "Operation: new = old")?)?;
+    let divisor = field(test, "Test: divisible by")?;
+    let divisor = match divisor.parse()? {
+        0 => return Err(divisor.error("cannot test divisibility by zero")),
+        n => n,
+    };
+    Ok(M::new(
+        items,
+        op,
+        op_line,
+        divisor,
+        field(if_true, "If true: throw to monkey")?.parse()?,
+        field(if_false, "If false: throw to monkey")?.parse()?,
+    ))
+}
+
+/// Every monkey in the notes, or every problem found reading them.
+fn parse_monkeys(input: &str) -> Result<Vec<M>, Vec<ScanError>> {
+    let groups: Vec<Vec<Line>> = Scanner::new(input).groups().collect();
+    let mut monkeys = vec![];
+    let mut problems = vec![];
+    for (id, group) in groups.iter().enumerate() {
+        match make_monkey(id, group) {
+            Ok(m) => {
+                for (target, line) in [(m.throw_on_true, &group[4]), (m.throw_on_false, &group[5])]
+                {
+                    if target >= groups.len() {
+                        let msg =
+                            format!("monkey {id} throws to monkey {target}, which does not exist");
+                        problems.push(line.token().error(msg));
+                    }
+                }
+                monkeys.push(m);
+            }
+            Err(e) => problems.push(e),
+        }
+    }
+    if groups.len() < 2 {
+        problems.push(ScanError::new(1, 1, "need at least two monkeys"));
+    }
+    match problems.is_empty() {
+        true => Ok(monkeys),
+        false => Err(problems),
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    parse_monkeys(input).err().unwrap_or_default()
+}
+
+/// `scale` items spread over eight monkeys.  The monkeys test divisibility
+/// by the first eight primes, as the real inputs do.  They never multiply by
+/// more than three, so part 1's worry levels can't overflow, however the
+/// items travel.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    const DIVISORS: [u64; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+    let monkeys = DIVISORS.len();
+    let mut items: Vec<Vec<u32>> = (0..monkeys).map(|_| vec![rng.gen_range(50..100)]).collect();
+    for _ in monkeys..scale {
+        items[rng.gen_range(0..monkeys)].push(rng.gen_range(50..100));
+    }
+
+    let mut divisors = DIVISORS;
+    divisors.shuffle(rng);
+    let mut out = String::new();
+    for (id, (items, divisor)) in items.iter().zip(divisors).enumerate() {
+        let op = match rng.gen_range(0..3) {
+            0 => format!("* {}", rng.gen_range(2..=3)),
+            _ => format!("+ {}", rng.gen_range(1..=8)),
+        };
+        let if_true = (id + rng.gen_range(1..monkeys)) % monkeys;
+        let mut if_false = (id + rng.gen_range(1..monkeys)) % monkeys;
+        if if_false == if_true {
+            if_false = (if_true + 1) % monkeys;
+            if if_false == id {
+                if_false = (id + 1) % monkeys;
+            }
+        }
+        let items: Vec<String> = items.iter().map(u32::to_string).collect();
+        if id > 0 {
+            out.push('\n');
+        }
+        writeln!(out, "Monkey {id}:").unwrap();
+        writeln!(out, "  Starting items: {}", items.join(", ")).unwrap();
+        writeln!(out, "  Operation: new = old {op}").unwrap();
+        writeln!(out, "  Test: divisible by {divisor}").unwrap();
+        writeln!(out, "    If true: throw to monkey {if_true}").unwrap();
+        writeln!(out, "    If false: throw to monkey {if_false}").unwrap();
+    }
+    out
+}
+
+fn load(input: &str) -> Result<Vec<M>> {
+    parse_monkeys(input).map_err(|problems| problems[0].clone().into())
+}
+
+fn part1(mut ms: Vec<M>) -> Result<u64> {
+    for i in 0..20 {
+        event!(Level::TRACE, "starting round {}", i);
+        for i in 0..ms.len() {
+            event!(Level::TRACE, "looking at monkey {}", i);
+            let items = std::mem::take(&mut ms[i].items);
+            for item in items {
+                ms[i].num_inspections += 1;
+                let level = ms[i].inspect(i, item)? / 3;
+                if level.is_multiple_of(ms[i].divisor) {
+                    let idx = ms[i].throw_on_true;
+                    ms[idx].items.push(level);
+                } else {
+                    let idx = ms[i].throw_on_false;
+                    ms[idx].items.push(level);
+                }
+            }
+            show_ms(&ms);
+        }
+    }
+    ms.sort_unstable_by(|a, b| b.num_inspections.partial_cmp(&a.num_inspections).unwrap());
+
+    for (i, m) in ms.iter().enumerate() {
+        event!(
+            Level::DEBUG,
+            "monkey {} inspected {} items",
+            i,
+            m.num_inspections
+        );
+    }
+    Ok(ms[0].num_inspections * ms[1].num_inspections)
+}
+
+fn part2(mut ms: Vec<M>) -> Result<u64> {
+    let divisor = ms
+        .iter()
+        .try_fold(1u64, |acc, m| acc.checked_mul(m.divisor))
+        .ok_or_else(|| anyhow!("the monkeys' divisors multiply past {}", u64::MAX))?;
+    for _ in 0..10000 {
+        for i in 0..ms.len() {
+            //println!("looking at monkey {}", i);
+            let items = std::mem::take(&mut ms[i].items);
+            for item in items {
+                ms[i].num_inspections += 1;
+                let level = ms[i].inspect(i, item)? % divisor;
+                if level.is_multiple_of(ms[i].divisor) {
+                    let idx = ms[i].throw_on_true;
+                    ms[idx].items.push(level);
+                } else {
+                    let idx = ms[i].throw_on_false;
+                    ms[idx].items.push(level);
+                }
+            }
+            //show_ms(&ms);
+        }
+    }
+    ms.sort_unstable_by(|a, b| b.num_inspections.partial_cmp(&a.num_inspections).unwrap());
+
+    for (i, m) in ms.iter().enumerate() {
+        event!(
+            Level::DEBUG,
+            "monkey {} inspected {} items",
+            i,
+            m.num_inspections
+        );
+    }
+    Ok(ms[0].num_inspections * ms[1].num_inspections)
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "monkey business after 20 rounds"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d11/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(load(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(part1(parsed.take())?.into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "monkey business after 10000 rounds without relief"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d11/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(load(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(part2(parsed.take())?.into())
+    }
+}
+
+fn show_ms(ms: &[M]) {
+    for (i, m) in ms.iter().enumerate() {
+        event!(Level::TRACE, "Monkey {} has {:?}", i, m.items)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    example_tests!(11: 10605, 2713310158_u64);
+
+    #[test]
+    fn test_overflow() {
+        let example = include_str!("example.txt");
+        // monkey 2 squares its items, and 2^32 squared is past u64
+        let input = example.replacen("79, 60, 97", "4294967296", 1);
+        assert_eq!(
+            Part1.run(&input).unwrap_err().to_string(),
+            "line 17: monkey 2's operation overflows on worry level 4294967296"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use crate::generate::Rng;
+use crate::geometry::{Grid, Point};
+use crate::scanner::ScanError;
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::{anyhow, Result};
+use priority_queue::PriorityQueue;
+use rand::Rng as _;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use tracing::{event, Level};
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 12,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+type Map = Grid<char>;
+
+/// The squares one step from `n` that are at most one higher.
+fn neighbors<M>(map: M, n: Point) -> Vec<Point>
+where
+    M: Borrow<Map>,
+{
+    let map = map.borrow();
+    let limit = (map[n] as u8 + 1) as char;
+    map.neighbors4(n).filter(|p| map[*p] <= limit).collect()
+}
+
+fn reconstruct_path(came_from: &HashMap<Point, Point>, mut current: Point) -> Vec<Point> {
+    let mut v = vec![current];
+
+    while let Some(previous) = came_from.get(&current) {
+        current = *previous;
+        v.push(current);
+    }
+    v.into_iter().rev().collect()
+}
+
+fn locate_all(map: &Map, item: char) -> Vec<Point> {
+    map.iter()
+        .filter(|(_, c)| **c == item)
+        .map(|(p, _)| p)
+        .collect()
+}
+
+fn astar<M: Borrow<Map>>(start: Point, goal: Point, map: M) -> Option<Vec<Point>> {
+    let map = map.borrow();
+    let h = |n: Point| n.manhattan(goal);
+    let mut open_set: PriorityQueue<Point, std::cmp::Reverse<u64>> = PriorityQueue::new();
+    open_set.push(start, std::cmp::Reverse(h(start)));
+    let mut came_from: HashMap<Point, Point> = HashMap::new();
+    let mut g_score: HashMap<Point, u64> = HashMap::new();
+    g_score.insert(start, 0);
+
+    let mut f_score: HashMap<Point, u64> = HashMap::new();
+    f_score.insert(start, h(start));
+
+    while !open_set.is_empty() {
+        //        println!("{} items in the open set:", open_set.len());
+        // for (i, (p, _)) in open_set.iter().enumerate() {
+        //     print!(
+        //         "{}. f{}/g{}\t",
+        //         i + 1,
+        //         f_score.get(p).copied().unwrap_or(0),
+        //         g_score.get(p).copied().unwrap_or(0)
+        //     );
+        //     for p in reconstruct_path(&came_from, *p) {
+        //         print!(" {:?} ", p);
+        //     }
+        //     print!("\n");
+        // }
+        let (current, _) = open_set.pop().unwrap();
+        //        println!("current: {:?}", current);
+        if current == goal {
+            return Some(reconstruct_path(&came_from, current));
+        }
+        let tentative_g = g_score.get(&current).copied().map_or(u64::MAX, |v| v + 1);
+        for neighbor in neighbors(map, current).into_iter() {
+            if tentative_g < g_score.get(&neighbor).copied().unwrap_or(u64::MAX) {
+                came_from.insert(neighbor, current);
+                g_score.insert(neighbor, tentative_g);
+                let this_f = tentative_g + h(neighbor);
+                // println!(
+                //     "from {:?} adding neighbor {:?} with g_score {} and f_score {}",
+                //     current, neighbor, tentative_g, this_f
+                // );
+                f_score.insert(neighbor, this_f);
+                open_set.push(neighbor, std::cmp::Reverse(this_f));
+            }
+        }
+    }
+    None
+}
+
+fn is_square(c: char) -> bool {
+    c.is_ascii_lowercase() || c == 'S' || c == 'E'
+}
+
+fn parse_map(input: &str) -> Result<Map> {
+    Ok(Grid::parse(&mut Scanner::new(input), |c| {
+        is_square(c).then_some(c)
+    })?)
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let mut problems = crate::lint::grid(input, is_square);
+    for (mark, what) in [('S', "start"), ('E', "end")] {
+        let mut scanner = Scanner::new(input);
+        let mut found = scanner.lines().flat_map(|line| {
+            line.text.match_indices(mark).map(move |(offset, _)| {
+                line.error_at(offset, format!("more than one {what} position"))
+            })
+        });
+        match found.next() {
+            Some(_) => problems.extend(found),
+            None => problems.push(ScanError::new(1, 1, format!("no {what} position"))),
+        }
+    }
+    problems
+}
+
+/// A map `scale` squares wide (at least 26, so it can climb from `a` to `z`
+/// a step at a time) and a quarter as high.  One row climbs steadily from `S`
+/// on the left to `E` on the right; the rest is noise around that slope.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let width = scale.max(26);
+    let height = (width / 4).max(2);
+    let path = rng.gen_range(0..height);
+    let mut out = String::with_capacity(height * (width + 1));
+    for y in 0..height {
+        for x in 0..width {
+            let slope = (x * 25 / (width - 1)) as u8;
+            let square = match (x, y == path) {
+                (0, true) => 'S',
+                (x, true) if x == width - 1 => 'E',
+                (_, true) => (b'a' + slope) as char,
+                _ if rng.gen_bool(0.3) => (b'a' + rng.gen_range(0..26)) as char,
+                _ => (b'a' + slope) as char,
+            };
+            out.push(square);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn part1(m: Map, start: Point, end: Point) -> Option<Vec<Point>> {
+    let path = astar(start, end, m)?;
+    for (i, p) in path.iter().enumerate() {
+        event!(Level::TRACE, "\t{}: {:?}", i, p);
+    }
+    Some(path)
+}
+
+fn part2<M: Borrow<Map>>(m: M, end: Point) -> Option<Vec<Point>> {
+    let a_pos = locate_all(m.borrow(), 'a');
+    let mut best_score = usize::MAX;
+    let mut best_path: Option<Vec<Point>> = None;
+
+    for &start in a_pos.iter() {
+        if let Some(path) = astar(start, end, m.borrow()) {
+            event!(
+                Level::DEBUG,
+                "path starting at {:?} has length: {}",
+                start,
+                path.len() - 1
+            );
+            if path.len() < best_score {
+                best_score = path.len();
+                best_path.replace(path);
+            }
+            // for (i, p) in path.iter().enumerate() {
+            //     println!("\t{}: {:?}", i, p);
+            // }
+        }
+    }
+    best_path
+}
+
+fn load(input: &str) -> Result<(Map, Point, Point)> {
+    let mut m = parse_map(input)?;
+    let start = m
+        .position(|c| *c == 'S')
+        .ok_or(anyhow!("no start position"))?;
+    let end = m
+        .position(|c| *c == 'E')
+        .ok_or(anyhow!("no end position"))?;
+    m[start] = 'a';
+    m[end] = 'z';
+    Ok((m, start, end))
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "fewest steps from the start to the summit"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d12/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(load(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let (m, start, end): (Map, Point, Point) = parsed.take();
+        match part1(m, start, end) {
+            Some(path) => Ok((path.len() - 1).into()),
+            None => Err(anyhow!("no path found")),
+        }
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "fewest steps from any lowest square to the summit"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d12/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(load(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let (m, _, end): (Map, Point, Point) = parsed.take();
+        match part2(m, end) {
+            Some(path) => Ok((path.len() - 1).into()),
+            None => Err(anyhow!("no paths found")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    example_tests!(12: 31, 29);
+}
@@ -0,0 +1,349 @@
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::{anyhow, bail, Result};
+use core::fmt::Display;
+use nom::branch::alt;
+use nom::bytes::complete::is_a;
+use nom::character::complete::{char, digit1};
+use nom::combinator::{all_consuming, map, map_res};
+use nom::multi::separated_list0;
+use nom::sequence::delimited;
+use nom::IResult;
+use rand::Rng as _;
+use std::fmt::Write;
+use tracing::{event, Level};
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 13,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Int(i32),
+    List(Vec<Item>),
+}
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Int(l0), Self::Int(r0)) => l0 == r0,
+            (Self::List(l0), Self::List(r0)) => l0 == r0,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Item::Int(i) => write!(f, "{}", i),
+            Item::List(v) => {
+                write!(f, "[")?;
+                for i in v.iter() {
+                    i.fmt(f)?;
+                    write!(f, ",")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+impl Eq for Item {}
+
+impl PartialOrd for Item {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Item {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering::*;
+        use Item::*;
+        match (self, other) {
+            (Int(i), Int(j)) => i.cmp(j),
+            (Int(i), List(v)) => {
+                let v1 = List(vec![Int(*i)]);
+                v1.cmp(&List(v.to_vec()))
+            }
+            (List(_), Int(i)) => {
+                let v2 = List(vec![Int(*i)]);
+                self.cmp(&v2)
+            }
+            (List(v1), List(v2)) => {
+                if v1.is_empty() {
+                    if !v2.is_empty() {
+                        return Less;
+                    } else {
+                        return Equal;
+                    }
+                }
+                if v2.is_empty() {
+                    return Greater;
+                }
+                match v1[0].cmp(&v2[0]) {
+                    Less => Less,
+                    Greater => Greater,
+                    Equal => List(v1[1..].to_vec()).cmp(&List(v2[1..].to_vec())),
+                }
+            }
+        }
+    }
+}
+
+fn digits(input: &str) -> IResult<&str, Item> {
+    map(map_res(digit1, str::parse), Item::Int)(input)
+}
+
+fn list(input: &str) -> IResult<&str, Item> {
+    map(
+        delimited(
+            char('['),
+            separated_list0(is_a(", "), parse_item),
+            char(']'),
+        ),
+        Item::List,
+    )(input)
+}
+
+fn parse_item(input: &str) -> IResult<&str, Item> {
+    let input = input.trim();
+    //    println!("parsing: {}", input);
+    alt((digits, list))(input)
+}
+
+fn parse_packet(line: &str) -> Result<Item> {
+    all_consuming(parse_item)(line.trim())
+        .map(|(_, item)| item)
+        .map_err(|e| anyhow!("bad packet: {}", e.to_owned()))
+}
+
+fn parse_line(line: &Line) -> Result<Item, ScanError> {
+    let token = line.token();
+    all_consuming(parse_item)(token.text)
+        .map(|(_, item)| item)
+        .map_err(|e| {
+            // point at wherever the parser gave up
+            let rest = match &e {
+                nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+                nom::Err::Incomplete(_) => "",
+            };
+            let offset = (rest.as_ptr() as usize)
+                .saturating_sub(token.text.as_ptr() as usize)
+                .min(token.text.len());
+            Line::from(token).error_at(offset, "bad packet")
+        })
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let mut problems = vec![];
+    for pair in Scanner::new(input).groups() {
+        if pair.len() != 2 {
+            problems
+                .push(pair[0].error(format!("expected a pair of packets, found {}", pair.len())));
+        }
+        problems.extend(pair.iter().filter_map(|line| parse_line(line).err()));
+    }
+    problems
+}
+
+/// `scale` pairs of packets, nested up to four lists deep.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    fn packet(rng: &mut Rng, depth: u32, out: &mut String) {
+        out.push('[');
+        for i in 0..rng.gen_range(0..=4) {
+            if i > 0 {
+                out.push(',');
+            }
+            if depth < 4 && rng.gen_bool(0.3) {
+                packet(rng, depth + 1, out);
+            } else {
+                write!(out, "{}", rng.gen_range(0..=10)).unwrap();
+            }
+        }
+        out.push(']');
+    }
+
+    let mut out = String::new();
+    for i in 0..scale.max(1) {
+        if i > 0 {
+            out.push('\n');
+        }
+        for _ in 0..2 {
+            packet(rng, 0, &mut out);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn parse_pairs(input: &str) -> Result<Vec<(Item, Item)>> {
+    Scanner::new(input)
+        .groups()
+        .map(|pair| {
+            let [first, second] = pair.as_slice() else {
+                bail!("line {}: expected a pair of packets", pair[0].number);
+            };
+            Ok((parse_line(first)?, parse_line(second)?))
+        })
+        .collect()
+}
+
+fn parse_packets(input: &str) -> Result<Vec<Item>, ScanError> {
+    Scanner::new(input)
+        .lines()
+        .filter(|line| !line.text.trim().is_empty())
+        .map(|line| parse_line(&line))
+        .collect()
+}
+
+fn part1(pairs: &[(Item, Item)]) -> usize {
+    let mut sum = 0;
+    for (i, (set1, set2)) in pairs.iter().enumerate() {
+        if let Some(std::cmp::Ordering::Less) = set1.partial_cmp(set2) {
+            sum += i + 1;
+        }
+    }
+    sum
+}
+
+fn part2(mut items: Vec<Item>) -> Result<usize> {
+    let m1 = parse_packet("[[2]]")?;
+    let m2 = parse_packet("[[6]]")?;
+
+    items.push(m1.clone());
+    items.push(m2.clone());
+
+    items.sort();
+
+    let p1 = items.iter().position(|i| m1.eq(i)).unwrap();
+    let p2 = items.iter().position(|i| m2.eq(i)).unwrap();
+
+    for (i, p) in items.iter().enumerate() {
+        event!(Level::TRACE, "{i}: {p}");
+    }
+    event!(Level::DEBUG, "p1: {}, p2: {}", p1 + 1, p2 + 1);
+    Ok((p1 + 1) * (p2 + 1))
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "sum of the indices of pairs in the right order"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d13/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse_pairs(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(part1(&parsed.take::<Vec<(Item, Item)>>()).into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "decoder key for the distress signal"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d13/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse_packets(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(part2(parsed.take())?.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nom::Finish;
+
+    example_tests!(13: 13, 140);
+
+    #[test]
+    fn test_ord() {
+        use std::cmp::Ordering;
+
+        fn do_test_ord(input: &str) -> Option<Ordering> {
+            let mut lines = input.lines();
+            let ((_, s1), (_, s2)) = (
+                parse_item(lines.next().unwrap()).finish().unwrap(),
+                parse_item(lines.next().unwrap()).finish().unwrap(),
+            );
+            s1.partial_cmp(&s2)
+        }
+
+        let input = r"[1,1,3,1,1]
+        [1,1,5,1,1]";
+
+        assert_eq!(do_test_ord(input), Some(Ordering::Less));
+
+        let input = "[[1],[2,3,4]]
+        [[1],4]";
+        assert_eq!(do_test_ord(input), Some(Ordering::Less));
+
+        let input = "[9]
+        [[8,7,6]]";
+        assert_eq!(do_test_ord(input), Some(Ordering::Greater));
+
+        let input = "[[4,4],4,4]
+        [[4,4],4,4,4]
+    ";
+        assert_eq!(do_test_ord(input), Some(Ordering::Less));
+
+        let input = "[7,7,7,7]
+        [7,7,7]";
+        assert_eq!(do_test_ord(input), Some(Ordering::Greater));
+
+        let input = "[]
+        [3]";
+        assert_eq!(do_test_ord(input), Some(Ordering::Less));
+
+        let input = "[[[]]]
+        [[]]";
+        assert_eq!(do_test_ord(input), Some(Ordering::Greater));
+
+        let input = "[1,[2,[3,[4,[5,6,7]]]],8,9]
+        [1,[2,[3,[4,[5,6,0]]]],8,9]";
+        assert_eq!(do_test_ord(input), Some(Ordering::Greater));
+    }
+}
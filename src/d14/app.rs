@@ -1,5 +1,5 @@
+use crate::geometry::Point;
 use crate::topo::{State, Topo};
-use crate::Coord;
 use egui::{
     pos2, Align, Align2, Color32, Label, Layout, Painter, Rect, ScrollArea, Stroke, TextStyle,
 };
@@ -10,7 +10,6 @@ pub struct App {
     cell_size: f32,
     border_size: f32,
     updates_per_second: u32,
-    spawn_rate: f64,
     last_updated: f64,
     last_spawn: f64,
     running: bool,
@@ -24,7 +23,6 @@ impl App {
             cell_size: 15.0,
             border_size: 1.0,
             updates_per_second: 2,
-            spawn_rate: 0.0,
             last_updated: 0.0,
             last_spawn: 0.0,
             running: true,
@@ -95,7 +93,7 @@ impl App {
         painter: &egui::Painter,
         xf: &RectTransform,
         font_id: &egui::FontId,
-        coord: Coord,
+        coord: Point,
         glyph: char,
     ) -> Rect {
         // println!("drawing {} at {:?}", glyph, coord);
@@ -112,12 +110,12 @@ impl App {
     }
 
     fn update_world(&mut self, try_drop: bool) {
-        if try_drop && !self.topo.drop_at(Coord::new(500, 0)) {
+        if try_drop && !self.topo.drop_at(Point::new(500, 0)) {
             println!("SFSFFSF");
             return;
         }
         let results = self.topo.step();
-        if results.len() == 0 {
+        if results.is_empty() {
             println!("No results");
         }
     }
@@ -150,7 +148,7 @@ impl eframe::App for App {
                         println!("Button 1");
                     }
                     if ui.button("Drop").clicked() {
-                        self.topo.drop_at(Coord::new(500, 0));
+                        self.topo.drop_at(Point::new(500, 0));
                         self.last_spawn = now;
                     }
                 })
@@ -3,7 +3,9 @@ use anyhow::{anyhow, Result};
 mod app;
 mod topo;
 
-use topo::{parse_lines, Coord};
+// topo.rs is shared with the library and reaches these through `crate::`
+use advent2022::{geometry, scanner};
+use topo::parse_lines;
 
 fn main() -> Result<()> {
     use app::App;
@@ -12,8 +14,8 @@ fn main() -> Result<()> {
 
     let input = include_str!("input.txt");
 
-    let topo = Topo::from_lines(parse_lines(input.lines())?);
-    //let topo: Topo = (0..20).map(|i| Coord::new(i, i)).collect();
+    let topo = Topo::from_lines(parse_lines(input)?)?;
+    //let topo: Topo = (0..20).map(|i| Point::new(i, i)).collect();
 
     let native_options = eframe::NativeOptions {
         initial_window_size: Some(vec2(800., 800.)),
@@ -25,6 +27,6 @@ fn main() -> Result<()> {
         native_options,
         Box::new(|cc| Box::new(App::new(cc, topo))),
     )
-    .map_err(|e| anyhow!("failed to start app: {}", e.to_string()))?; // gotta be a better way to do this
+    .map_err(|e| anyhow!("failed to start app: {}", e))?; // gotta be a better way to do this
     Ok(())
 }
@@ -1,40 +1,103 @@
-use crate::d14::topo::{parse_lines, Coord, StepResult, Topo};
-use crate::PuzzleRun;
+use crate::d14::topo::{parse_line, parse_lines, StepResult, Topo};
+use crate::generate::Rng;
+use crate::geometry::Point;
+use crate::scanner::ScanError;
+use crate::{Answer, Parsed, PuzzleRun};
+use anyhow::bail;
+use rand::Rng as _;
+use std::fmt::Write;
 
+// shared with the `day14` visualizer; the items only one of the two uses
+// allow dead code where they're defined
 mod topo;
+
 struct Part1;
 
 struct Part2;
 
 pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
-    vec![Box::new(Part2)]
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 14,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    crate::lint::each_line(input, parse_line)
+}
+
+/// `scale` rock paths of two to five points, in a cave whose depth grows
+/// with `scale` up to 200.  Nothing blocks the sand's source at `500,0`.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let depth = 20 + scale.min(180) as i64;
+    let mut out = String::new();
+    for _ in 0..scale.max(1) {
+        let mut point = Point::new(
+            rng.gen_range(500 - depth..=500 + depth),
+            rng.gen_range(2..=depth),
+        );
+        let mut points = vec![format!("{},{}", point.x, point.y)];
+        for i in 0..rng.gen_range(1..=4) {
+            let step = rng.gen_range(1..=6) * if rng.gen_bool(0.5) { 1 } else { -1 };
+            if i % 2 == 0 {
+                point.x += step;
+            } else {
+                point.y = (point.y + step).clamp(2, depth);
+            }
+            points.push(format!("{},{}", point.x, point.y));
+        }
+        writeln!(out, "{}", points.join(" -> ")).unwrap();
+    }
+    out
 }
 
 impl PuzzleRun for Part1 {
-    fn input_data(&self) -> anyhow::Result<&str> {
-        crate::read_file("src/d14/input-test.txt")
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "units of sand at rest before sand falls into the abyss"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d14/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(Topo::from_lines(parse_lines(input)?)?))
     }
 
-    fn run(&self, input: &str) -> String {
-        let mut topo = Topo::from_lines(parse_lines(input.lines()).unwrap());
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let mut topo: Topo = parsed.take();
         let mut grain_count = 0;
-        topo.drop_at(Coord::new(500, 0));
+        topo.drop_at(Point::new(500, 0));
 
         loop {
             let results = topo.step();
             if results.len() != 1 {
-                panic!();
+                bail!("expected one falling grain, found {}", results.len());
             }
             match results[0] {
                 StepResult::Moved(_from, _to) => {}
                 StepResult::Stopped(_p) => {
-                    if !topo.drop_at(Coord::new(500, 0)) {
-                        panic!()
+                    if !topo.drop_at(Point::new(500, 0)) {
+                        bail!("sand source blocked before any sand fell into the abyss");
                     }
                     grain_count += 1;
                 }
                 StepResult::Off(_p) => {
-                    return format!("{}", grain_count);
+                    return Ok(grain_count.into());
                 }
             }
         }
@@ -42,33 +105,54 @@ impl PuzzleRun for Part1 {
 }
 
 impl PuzzleRun for Part2 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "units of sand at rest once the source is blocked"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d14/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        let mut topo = Topo::from_lines(parse_lines(input.lines()).unwrap());
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(Topo::from_lines(parse_lines(input)?)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let mut topo: Topo = parsed.take();
         topo.with_floor();
         let mut grain_count = 1;
-        topo.drop_at(Coord::new(500, 0));
+        topo.drop_at(Point::new(500, 0));
 
         loop {
             let results = topo.step();
             if results.len() != 1 {
-                panic!();
+                bail!("expected one falling grain, found {}", results.len());
             }
             match results[0] {
                 StepResult::Moved(_from, _to) => {}
                 StepResult::Stopped(_p) => {
-                    if !topo.drop_at(Coord::new(500, 0)) {
-                        return format!("{}", grain_count);
+                    if !topo.drop_at(Point::new(500, 0)) {
+                        return Ok(grain_count.into());
                     }
                     grain_count += 1;
                 }
                 StepResult::Off(_p) => {
-                    panic!("This should never happen");
+                    bail!("sand fell off the floor");
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    example_tests!(14: 24, 93);
+}
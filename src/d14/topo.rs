@@ -1,28 +1,18 @@
-use anyhow::{anyhow, Result};
+use crate::geometry::Point;
+use crate::scanner::{self, ScanError, Scanner};
+use anyhow::{bail, Result};
 use std::collections::HashMap;
 use std::fmt::Debug;
 
-#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
-pub(crate) struct Coord {
-    pub(crate) x: usize,
-    pub(crate) y: usize,
-}
-
-impl Coord {
-    pub(crate) fn new(x: usize, y: usize) -> Self {
-        Coord { x, y }
-    }
-}
-
 #[derive(PartialEq, Eq, Debug)]
 pub(crate) struct Line {
-    pub(crate) points: Vec<Coord>,
+    pub(crate) points: Vec<Point>,
 }
 
-impl FromIterator<Coord> for Line {
+impl FromIterator<Point> for Line {
     fn from_iter<T>(iter: T) -> Self
     where
-        T: IntoIterator<Item = Coord>,
+        T: IntoIterator<Item = Point>,
     {
         let mut points = vec![];
         for i in iter {
@@ -40,67 +30,48 @@ pub(crate) enum State {
 
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct Bounds {
-    pub upper_left: Coord,
-    pub lower_right: Coord,
+    pub upper_left: Point,
+    pub lower_right: Point,
 }
 
 impl Bounds {
-    fn new_from_coord(c: Coord) -> Self {
+    fn new_from_coord(c: Point) -> Self {
         Self {
             upper_left: c,
             lower_right: c,
         }
     }
 
-    fn get_width(&self) -> usize {
-        self.lower_right.x - self.upper_left.x
-    }
-
-    fn get_height(&self) -> usize {
-        self.lower_right.y - self.upper_left.y
-    }
-
-    fn update(&mut self, c: Coord) {
-        self.upper_left = Coord::new(
+    fn update(&mut self, c: Point) {
+        self.upper_left = Point::new(
             std::cmp::min(self.upper_left.x, c.x),
             std::cmp::min(self.upper_left.y, c.y),
         );
-        self.lower_right = Coord::new(
+        self.lower_right = Point::new(
             std::cmp::max(self.lower_right.x, c.x),
             std::cmp::max(self.lower_right.y, c.y),
         );
     }
-
-    fn maybe_expand(&self, ul: Coord, lr: Coord) -> Self {
-        Self {
-            upper_left: Coord::new(
-                std::cmp::min(self.upper_left.x, ul.x),
-                std::cmp::max(self.upper_left.y, ul.y),
-            ),
-            lower_right: Coord::new(
-                std::cmp::max(self.lower_right.x, lr.x),
-                std::cmp::min(self.lower_right.y, lr.y),
-            ),
-        }
-    }
 }
 
-impl From<Coord> for Bounds {
-    fn from(c: Coord) -> Self {
+impl From<Point> for Bounds {
+    fn from(c: Point) -> Self {
         Bounds::new_from_coord(c)
     }
 }
 pub(crate) struct Topo {
-    data: HashMap<Coord, State>,
+    data: HashMap<Point, State>,
     bounds: Bounds,
-    active: Vec<Coord>,
-    floor: Option<usize>,
+    active: Vec<Point>,
+    floor: Option<i64>,
 }
 
 pub(crate) enum StepResult {
-    Moved(Coord, Coord),
-    Stopped(Coord),
-    Off(Coord),
+    Moved(Point, Point),
+    // where a grain stops or leaves is for the puzzle; the visualizer only
+    // redraws the map
+    Stopped(#[allow(dead_code)] Point),
+    Off(#[allow(dead_code)] Point),
 }
 
 /*
@@ -113,12 +84,12 @@ pub(crate) struct TopoCoordIterator {
 }
 
 impl Iterator for TopoCoordIterator {
-    type Item = Coord;
+    type Item = Point;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.row < self.height {
             if self.col < self.width {
-                let c = Coord::new(self.col + self.x_offset, self.row);
+                let c = Point::new(self.col + self.x_offset, self.row);
                 if self.col + 1 < self.width {
                     self.col += 1;
                 } else {
@@ -133,10 +104,10 @@ impl Iterator for TopoCoordIterator {
 }
 */
 
-impl FromIterator<Coord> for Topo {
-    fn from_iter<I: IntoIterator<Item = Coord>>(iter: I) -> Self {
-        let mut data: HashMap<Coord, State> = Default::default();
-        let mut a_coord: Option<Coord> = None;
+impl FromIterator<Point> for Topo {
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        let mut data: HashMap<Point, State> = Default::default();
+        let mut a_coord: Option<Point> = None;
 
         for c in iter {
             if a_coord.is_none() {
@@ -180,11 +151,9 @@ impl std::fmt::Debug for Topo {
             None => (ul.x, lr.x),
         };
 
-        //.maybe_expand(Coord::new(0, 0), Coord::new(20, 20));
-
         for y in 0..(display_floor + 1) {
             for x in start_x..(end_x + 1) {
-                let s = match self[Coord::new(x, y)] {
+                let s = match self[Point::new(x, y)] {
                     _ if y == lr.y + 2 => "=",
                     State::Empty => ".",
                     State::Sand => "o",
@@ -203,110 +172,84 @@ impl std::fmt::Debug for Topo {
     }
 }
 
+// What the visualizer needs to draw the map; the puzzle never looks at it.
+#[allow(dead_code)]
 impl Topo {
-    /*
-        fn offset(&self, x: usize, y: usize) -> usize {
-            y * self.width + (x - self.x_offset)
-        }
-    */
-
     pub(crate) fn get_bounds(&self) -> Bounds {
         self.bounds
     }
 
-    pub(crate) fn get_width(&self) -> usize {
-        self.bounds.get_width()
+    pub(crate) fn get_width(&self) -> i64 {
+        self.bounds.lower_right.x - self.bounds.upper_left.x
     }
 
-    pub(crate) fn get_height(&self) -> usize {
-        self.bounds.get_height()
+    pub(crate) fn get_height(&self) -> i64 {
+        self.bounds.lower_right.y - self.bounds.upper_left.y
     }
 
-    pub(crate) fn get_x_offset(&self) -> usize {
+    pub(crate) fn get_x_offset(&self) -> i64 {
         self.bounds.upper_left.x
     }
 
+    pub(crate) fn coord_iter(&self) -> impl Iterator<Item = (Point, State)> + '_ {
+        self.data
+            .iter()
+            .map(|(c_ref, s_ref)| (c_ref.to_owned(), s_ref.to_owned()))
+    }
+}
+
+impl Topo {
+    /*
+        fn offset(&self, x: usize, y: usize) -> usize {
+            y * self.width + (x - self.x_offset)
+        }
+    */
+
+    // the visualizer drops sand without a floor
+    #[allow(dead_code)]
     pub(crate) fn with_floor(&mut self) {
         self.floor = Some(self.bounds.lower_right.y + 2);
     }
 
-    pub(crate) fn from_lines(lines: Vec<Line>) -> Self {
-        let mut data: HashMap<Coord, State> = Default::default();
-        let mut c: Coord = Default::default();
-        let mut bounds = Bounds::new_from_coord(lines[0].points[0]);
+    pub(crate) fn from_lines(lines: Vec<Line>) -> Result<Self> {
+        let mut data: HashMap<Point, State> = Default::default();
+        let Some(&first) = lines.first().and_then(|l| l.points.first()) else {
+            bail!("no rock paths found");
+        };
+        let mut bounds = Bounds::new_from_coord(first);
 
         // mark the first point.   then we can just mark remaining points below
-        data.insert(lines[0].points[0], State::Wall);
-
-        for line in lines.iter() {
-            for i in 0..line.points.len() - 1 {
-                let start = line.points[i];
-                let end = line.points[i + 1];
-
-                if start.x == end.x {
-                    // vertical
-                    let dy: i32 = end.y as i32 - start.y as i32;
-                    c.x = start.x;
-                    match dy.cmp(&0) {
-                        std::cmp::Ordering::Greater => {
-                            for j in 0..=(dy as usize) {
-                                c.y = start.y + j;
-                                data.insert(c, State::Wall);
-                                bounds.update(c);
-                            }
-                        }
-                        std::cmp::Ordering::Less => {
-                            let dy = (-dy) as usize;
-                            for j in 0..=dy {
-                                c.y = start.y - j;
-                                data.insert(c, State::Wall);
-                                bounds.update(c);
-                            }
-                        }
-                        _ => {}
-                    }
-                } else if start.y == end.y {
-                    // horizontal
-                    c.y = start.y;
-                    let dx: i32 = end.x as i32 - start.x as i32;
-                    match dx.cmp(&0) {
-                        std::cmp::Ordering::Greater => {
-                            for j in 0..=(dx as usize) {
-                                c.x = start.x + j;
-                                data.insert(c, State::Wall);
-                                bounds.update(c);
-                            }
-                        }
-                        std::cmp::Ordering::Less => {
-                            let dx = (-dx) as usize;
-                            for j in 0..=dx {
-                                c.x = start.x - j;
-                                data.insert(c, State::Wall);
-                                bounds.update(c);
-                            }
-                        }
-                        _ => {}
-                    }
-                } else {
-                    panic!()
+        data.insert(first, State::Wall);
+
+        for (n, line) in lines.iter().enumerate() {
+            for segment in line.points.windows(2) {
+                let (start, end) = (segment[0], segment[1]);
+                let gap = end - start;
+                if gap.dx != 0 && gap.dy != 0 {
+                    bail!(
+                        "line {}: path from {} to {} is not horizontal or vertical",
+                        n + 1,
+                        start,
+                        end
+                    );
+                }
+                let mut c = start;
+                for _ in 0..=gap.chebyshev() {
+                    data.insert(c, State::Wall);
+                    bounds.update(c);
+                    c += gap.signum();
                 }
             }
         }
-        Topo {
+        Ok(Topo {
             data,
             bounds,
             active: vec![],
             floor: None,
-        }
-    }
-
-    pub(crate) fn coord_iter(&self) -> impl Iterator<Item = (Coord, State)> + '_ {
-        self.data
-            .iter()
-            .map(|(c_ref, s_ref)| (c_ref.to_owned(), s_ref.to_owned()))
+        })
     }
 
-    pub(crate) fn drop_at(&mut self, c: Coord) -> bool {
+    pub(crate) fn drop_at(&mut self, c: Point) -> bool {
         if matches!(self[c], State::Empty) {
             self[c] = State::Sand;
             self.active.push(c);
@@ -338,23 +281,18 @@ impl Topo {
         rs
     }
 
-    fn next_pos(&self, p: Coord) -> StepResult {
+    fn next_pos(&self, p: Point) -> StepResult {
         let current_bound = self.bounds;
 
         if let Some(floor) = self.floor {
             if p.y + 1 == floor {
                 return StepResult::Stopped(p);
             }
-        } else {
-            if p.y == current_bound.lower_right.y {
-                return StepResult::Off(p);
-            }
+        } else if p.y == current_bound.lower_right.y {
+            return StepResult::Off(p);
         }
 
-        let mut c: Coord = Default::default();
-
-        c.x = p.x;
-        c.y = p.y + 1;
+        let mut c = Point::new(p.x, p.y + 1);
         if let State::Empty = self[c] {
             // move down
             return StepResult::Moved(p, c);
@@ -385,16 +323,16 @@ impl Topo {
     }
 
     /*
-        fn step_coord(&mut self, p: Coord) -> StepResult {
+        fn step_coord(&mut self, p: Point) -> StepResult {
             if !matches!(self[p], State::Sand) {
-                panic!("no grain currently at Coord {:?}", p);
+                panic!("no grain currently at Point {:?}", p);
             }
             let current_bound = self.bounds;
 
             if p.y >= current_bound.lower_right.y {
                 if self.floor {
                     if p.y == current_bound.lower_right.y {
-                        return StepResult::Moved(Coord::new(p.x, p.y + 1));
+                        return StepResult::Moved(Point::new(p.x, p.y + 1));
                     } else {
                         return StepResult::Stopped;
                     }
@@ -403,7 +341,7 @@ impl Topo {
                     return StepResult::Off;
                 }
             }
-            let mut c: Coord = Default::default();
+            let mut c: Point = Default::default();
 
             c.x = p.x;
             c.y = p.y + 1;
@@ -435,44 +373,43 @@ impl Topo {
         }
     */
 }
-impl std::ops::Index<Coord> for Topo {
+impl std::ops::Index<Point> for Topo {
     type Output = State;
 
-    fn index(&self, index: Coord) -> &Self::Output {
+    fn index(&self, index: Point) -> &Self::Output {
         self.data.get(&index).unwrap_or(&State::Empty)
     }
 }
-impl std::ops::IndexMut<Coord> for Topo {
-    fn index_mut(&mut self, index: Coord) -> &mut Self::Output {
+impl std::ops::IndexMut<Point> for Topo {
+    fn index_mut(&mut self, index: Point) -> &mut Self::Output {
         let r = self.data.entry(index).or_insert(State::Empty);
         self.bounds.update(index);
         r
     }
 }
 
-fn parse_line(input: &str) -> Result<Line> {
-    input
-        .split("->")
-        .map(|s| s.trim())
-        .map(|s| match s.find(',') {
-            Some(i) => Ok(Coord {
-                x: s[0..i].parse().unwrap(),
-                y: s[i + 1..].parse().unwrap(),
-            }),
-            None => Err(anyhow!("bad coordinate: {s}")),
-        })
-        .collect()
+pub(crate) fn parse_line(input: &scanner::Line) -> Result<Line, ScanError> {
+    let mut points: Vec<Point> = vec![];
+    for token in input.split("->") {
+        let (x, y) = token.pair(",")?;
+        let point = Point::new(x, y);
+        if let Some(&prev) = points.last() {
+            if prev.x != point.x && prev.y != point.y {
+                return Err(token.error(format!(
+                    "path from {prev} to {point} is not horizontal or vertical"
+                )));
+            }
+        }
+        points.push(point);
+    }
+    Ok(Line { points })
 }
 
-pub(crate) fn parse_lines<'a, T>(lines: T) -> Result<Vec<Line>>
-where
-    T: Iterator<Item = &'a str>,
-{
-    lines
-        .into_iter()
-        .map(|s| s.trim())
-        .map(|l| parse_line(l))
-        .collect::<Result<Vec<Line>, _>>()
+pub(crate) fn parse_lines(input: &str) -> Result<Vec<Line>> {
+    Ok(Scanner::new(input)
+        .lines()
+        .map(|line| parse_line(&line))
+        .collect::<Result<Vec<Line>, _>>()?)
 }
 
 #[cfg(test)]
@@ -482,27 +419,30 @@ mod test {
 
     #[test]
     fn test_parse() {
-        let line = parse_line("508,146 -> 513,146").unwrap();
+        let line = parse_line(&scanner::Line::new(1, "508,146 -> 513,146")).unwrap();
         assert_eq!(
             line,
             Line {
-                points: vec![Coord::new(508, 146), Coord::new(513, 146)]
+                points: vec![Point::new(508, 146), Point::new(513, 146)]
             }
         );
+
+        let err = parse_line(&scanner::Line::new(3, "1,1 -> 1,4 -> 3,6")).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 15));
     }
 
     #[test]
     fn test_print() {
         let input = "498,4 -> 498,6 -> 496,6
             503,4 -> 502,4 -> 502,9 -> 494,9";
-        let topo = Topo::from_lines(parse_lines(input.lines()).unwrap());
+        let topo = Topo::from_lines(parse_lines(input).unwrap()).unwrap();
         println!("{topo:?}");
     }
 
     #[test]
     fn test_print_large() {
         let input = include_str!("input.txt");
-        let topo = Topo::from_lines(parse_lines(input.lines()).unwrap());
+        let topo = Topo::from_lines(parse_lines(input).unwrap()).unwrap();
         println!("{topo:?}");
     }
 }
@@ -1,8 +1,15 @@
-use crate::PuzzleRun;
-use anyhow::Result;
+use crate::geometry::{Point, Vector};
+use crate::scanner::{Line, ScanError};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::{anyhow, Result};
 use regex::Regex;
 
+use crate::generate::Rng;
+use rand::Rng as _;
 use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::LazyLock;
+use tracing::{event, Level};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct Interval {
@@ -19,6 +26,7 @@ impl From<[i64; 2]> for Interval {
     }
 }
 impl Interval {
+    #[cfg(test)]
     pub(crate) fn new(low: i64, high: i64) -> Self {
         if high < low {
             panic!();
@@ -36,19 +44,6 @@ impl From<Vec<Interval>> for Intervals {
     }
 }
 
-struct IntervalIter<'a> {
-    pos: usize,
-    iter: std::slice::Iter<'a, Interval>,
-}
-
-impl<'a> Iterator for IntervalIter<'a> {
-    type Item = &'a Interval;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
-    }
-}
-
 impl core::fmt::Debug for Intervals {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -70,6 +65,7 @@ impl Intervals {
         }
     }
 
+    #[cfg(test)]
     fn iter(&self) -> std::slice::Iter<'_, Interval> {
         self.intervals.iter()
     }
@@ -81,18 +77,18 @@ impl Intervals {
     fn compress(&mut self) {
         let mut i = 0;
 
-        while i < self.intervals.len() - 1 {
+        while i + 1 < self.intervals.len() {
             if self.intervals[i].high + 1 == self.intervals[i + 1].low {
                 self.intervals[i].high = self.intervals[i + 1].high;
                 self.intervals.remove(i + 1);
             } else {
-                i = i + 1;
+                i += 1;
             }
         }
     }
 
     fn merge(&mut self, new_i: Interval) {
-        if self.intervals.len() == 0 {
+        if self.intervals.is_empty() {
             self.intervals.insert(0, new_i);
             return;
         }
@@ -147,74 +143,26 @@ impl Intervals {
 struct Part1;
 
 pub(crate) fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
-    vec![Box::new(Part2)]
+    vec![Box::new(Part1), Box::new(Part2)]
 }
 
-fn test_input_data() -> anyhow::Result<&'static str> {
-    Ok("Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-    Sensor at x=9, y=16: closest beacon is at x=10, y=16
-    Sensor at x=13, y=2: closest beacon is at x=15, y=3
-    Sensor at x=12, y=14: closest beacon is at x=10, y=16
-    Sensor at x=10, y=20: closest beacon is at x=10, y=16
-    Sensor at x=14, y=17: closest beacon is at x=10, y=16
-    Sensor at x=8, y=7: closest beacon is at x=2, y=10
-    Sensor at x=2, y=0: closest beacon is at x=2, y=10
-    Sensor at x=0, y=11: closest beacon is at x=2, y=10
-    Sensor at x=20, y=14: closest beacon is at x=25, y=17
-    Sensor at x=17, y=20: closest beacon is at x=21, y=22
-    Sensor at x=16, y=7: closest beacon is at x=15, y=3
-    Sensor at x=14, y=3: closest beacon is at x=15, y=3
-    Sensor at x=20, y=1: closest beacon is at x=15, y=3")
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct Coord {
-    x: i64,
-    y: i64,
-}
-
-impl Coord {
-    fn new(x: i64, y: i64) -> Self {
-        Self { x, y }
-    }
-
-    fn dist(&self, other: &Coord) -> u64 {
-        TryInto::<u64>::try_into(self.x.abs_diff(other.x) + self.y.abs_diff(other.y)).unwrap()
-    }
-}
-
-impl std::fmt::Display for Coord {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+inventory::submit! {
+    crate::Day {
+        number: 15,
+        runs: get_runs,
+        validate,
+        generate,
     }
 }
 
 #[derive(Debug, Copy, Clone)]
 struct Sensor {
-    loc: Coord,
-    closest_beacon: Coord,
-}
-
-#[derive(Debug)]
-struct SensorParseError {
-    msg: String,
-}
-
-impl SensorParseError {
-    fn new(msg: String) -> Self {
-        Self { msg }
-    }
-}
-impl From<std::num::ParseIntError> for SensorParseError {
-    fn from(value: std::num::ParseIntError) -> Self {
-        Self {
-            msg: value.to_string(),
-        }
-    }
+    loc: Point,
+    closest_beacon: Point,
 }
 
 impl Sensor {
-    fn new(s: Coord, b: Coord) -> Self {
+    fn new(s: Point, b: Point) -> Self {
         Self {
             loc: s,
             closest_beacon: b,
@@ -222,29 +170,78 @@ impl Sensor {
     }
 }
 
-impl std::str::FromStr for Sensor {
-    type Err = SensorParseError;
+static SENSOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$")
+        .unwrap()
+});
+
+fn parse_sensor(line: &Line) -> Result<Sensor, ScanError> {
+    let token = line.token();
+    let Some(captures) = SENSOR.captures(token.text) else {
+        return Err(token.error("expected `Sensor at x=X, y=Y: closest beacon is at x=X, y=Y`"));
+    };
+    let mut coords = [0i64; 4];
+    for (coord, m) in coords.iter_mut().zip(captures.iter().skip(1).flatten()) {
+        *coord = m
+            .as_str()
+            .parse()
+            .map_err(|e| Line::from(token).error_at(m.start(), format!("bad coordinate: {e}")))?;
+    }
+    let [sx, sy, bx, by] = coords;
+    Ok(Sensor::new(Point::new(sx, sy), Point::new(bx, by)))
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    if input.trim().is_empty() {
+        return vec![no_sensors()];
+    }
+    crate::lint::each_line(input, parse_sensor)
+}
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let re = Regex::new(
-            r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)",
+/// `scale` sensors across the four-million-square search area, with reach
+/// shrinking as there are more of them so that some of the area stays
+/// uncovered.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let sensors = scale.max(1);
+    let reach = (2_000_000.0 / (sensors as f64).sqrt()) as i64;
+    let mut out = String::new();
+    for _ in 0..sensors {
+        let sensor = Point::new(rng.gen_range(0..=4_000_000), rng.gen_range(0..=4_000_000));
+        let distance = rng.gen_range(1..=reach);
+        let dx = rng.gen_range(-distance..=distance);
+        let dy = (distance - dx.abs()) * if rng.gen_bool(0.5) { 1 } else { -1 };
+        let beacon = sensor + Vector::new(dx, dy);
+        writeln!(
+            out,
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            sensor.x, sensor.y, beacon.x, beacon.y
         )
         .unwrap();
-        match re.captures(s).map(|c| c.extract()) {
-            Some((_, [sx, sy, bx, by])) => Ok(Sensor::new(
-                Coord::new(sx.parse()?, sy.parse()?),
-                Coord::new(bx.parse()?, by.parse()?),
-            )),
-            None => Err(SensorParseError::new(format!("parse failure: {}", s))),
-        }
     }
+    out
 }
 
-fn span_for_line(sensors: &Vec<Sensor>, _beacons: &HashSet<Coord>, the_row: i64) -> Intervals {
+/// At least one sensor, one per line.
+fn parse_sensors(input: &str) -> Result<Vec<Sensor>> {
+    let sensors: Vec<Sensor> = Scanner::new(input)
+        .lines()
+        .map(|line| parse_sensor(&line))
+        .collect::<Result<_, _>>()?;
+    if sensors.is_empty() {
+        return Err(no_sensors().into());
+    }
+    Ok(sensors)
+}
+
+fn no_sensors() -> ScanError {
+    ScanError::new(1, 1, "expected a sensor, found end of input")
+}
+
+fn span_for_line(sensors: &[Sensor], _beacons: &HashSet<Point>, the_row: i64) -> Intervals {
     let mut intervals = Intervals::new();
 
     for s in sensors.iter() {
-        let radius = s.loc.dist(&s.closest_beacon);
+        let radius = s.loc.manhattan(s.closest_beacon);
 
         let dy = TryInto::<i64>::try_into(radius).unwrap() - (s.loc.y - the_row).abs();
         // println!("Sensor at {}\tRadius: {}\tDistance: {}", s.loc, radius, dy);
@@ -259,69 +256,122 @@ fn span_for_line(sensors: &Vec<Sensor>, _beacons: &HashSet<Coord>, the_row: i64)
     }
     intervals
 }
-impl Part1 {}
+/// The puzzle asks about row 2000000 and searches `0..=4000000` for the
+/// distress beacon; its example asks about row 10 and searches `0..=20`.
+const ROW: i64 = 2_000_000;
+const LIMIT: i64 = 4_000_000;
+const EXAMPLE_ROW: i64 = 10;
+const EXAMPLE_LIMIT: i64 = 20;
+
+/// How many positions in `row` can't hold a beacon.
+fn no_beacon_count(sensors: &[Sensor], row: i64) -> u32 {
+    let beacons: HashSet<Point> = sensors.iter().map(|s| s.closest_beacon).collect();
+    let intervals = span_for_line(sensors, &beacons, row);
+    event!(Level::DEBUG, "{:?}", intervals);
+    intervals.span() - beacons.iter().filter(|c| c.y == row).count() as u32
+}
+
+/// The tuning frequency of the one position in `0..=limit` on both axes
+/// that no sensor covers.
+fn tuning_frequency(sensors: &[Sensor], limit: i64) -> Result<i64> {
+    let beacons: HashSet<Point> = sensors.iter().map(|s| s.closest_beacon).collect();
+    for row in 0..=limit {
+        let mut intervals = span_for_line(sensors, &beacons, row);
+        intervals.compress();
+        if intervals.interval_count() > 1 {
+            let col = intervals.intervals[0].high + 1;
+            event!(Level::DEBUG, "row: {}, col: {}", row, col);
+            return Ok(col * 4_000_000 + row);
+        }
+    }
+    Err(anyhow!(
+        "no uncovered position found for the distress beacon"
+    ))
+}
 
 impl PuzzleRun for Part1 {
-    fn input_data(&self) -> anyhow::Result<&str> {
-        //test_input_data()
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "positions in the target row that cannot hold a beacon"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d15/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        let sensors: Vec<Sensor> = input
-            .lines()
-            .map(str::parse::<Sensor>)
-            .collect::<Result<Vec<Sensor>, _>>()
-            .unwrap();
-
-        let beacons: HashSet<Coord> = sensors.iter().map(|s| s.closest_beacon).collect();
-        let the_row: i64 = 2_000_000;
-        let intervals = span_for_line(&sensors, &beacons, the_row);
-        println!("{:?}", intervals);
-        format!(
-            "{}",
-            intervals.span() - beacons.iter().filter(|c| c.y == the_row).count() as u32
-        )
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse_sensors(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(no_beacon_count(&parsed.take::<Vec<Sensor>>(), ROW).into())
+    }
+
+    fn solve_example(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(no_beacon_count(&parsed.take::<Vec<Sensor>>(), EXAMPLE_ROW).into())
     }
 }
 
 struct Part2;
 
 impl PuzzleRun for Part2 {
-    fn input_data(&self) -> anyhow::Result<&str> {
-        //test_input_data()
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "tuning frequency of the distress beacon"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d15/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        let sensors: Vec<Sensor> = input
-            .lines()
-            .map(str::parse::<Sensor>)
-            .collect::<Result<Vec<Sensor>, _>>()
-            .unwrap();
-
-        let beacons: HashSet<Coord> = sensors.iter().map(|s| s.closest_beacon).collect();
-        for row in 0..=4_000_000 {
-            let mut intervals = span_for_line(&sensors, &beacons, row);
-            intervals.compress();
-            if intervals.interval_count() > 1 {
-                let col = intervals.intervals[0].high + 1;
-                println!("row: {}, col: {}", row, col);
-                return format!("{}", col * 4_000_000 + row);
-            }
-        }
-        "none".to_string()
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse_sensors(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(tuning_frequency(&parsed.take::<Vec<Sensor>>(), LIMIT)?.into())
+    }
+
+    fn solve_example(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(tuning_frequency(&parsed.take::<Vec<Sensor>>(), EXAMPLE_LIMIT)?.into())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::d15::*;
 
-    use crate::d15::{Interval, Intervals};
+    example_tests!(15: 26, 56000011);
+
+    #[test]
+    fn test_parameters() {
+        let example = include_str!("example.txt");
+        let sensors = parse_sensors(example).unwrap();
+        assert_eq!(no_beacon_count(&sensors, EXAMPLE_ROW), 26);
+        // the row comes from the caller, not from the shape of the input
+        assert_eq!(no_beacon_count(&sensors, 9), 25);
+        assert_eq!(Part1.run(example).unwrap(), Answer::Int(0));
+        assert_eq!(tuning_frequency(&sensors, EXAMPLE_LIMIT).unwrap(), 56000011);
+        assert!(tuning_frequency(&sensors, 10).is_err());
+    }
 
     #[test]
     fn test_intervals() {
-        let mut is: Intervals = vec![].into();
+        let is: Intervals = vec![].into();
         let mut it = is.iter();
         assert_eq!(it.next(), None);
 
@@ -337,7 +387,7 @@ mod test {
         assert_eq!(it.next(), Some(&Interval::new(2, 2)));
         assert_eq!(it.next(), None);
 
-        let mut is: Intervals = vec![[1, 4].into(), [7, 8].into()].into();
+        let is: Intervals = vec![[1, 4].into(), [7, 8].into()].into();
         let mut it = is.iter();
         assert_eq!(it.next(), Some(&Interval::new(1, 4)));
         assert_eq!(it.next(), Some(&Interval::new(7, 8)));
@@ -454,15 +504,36 @@ mod test {
         is.merge([1611175, 3652823].into());
     }
 
+    #[test]
+    fn test_no_sensors() {
+        assert_eq!(validate("").len(), 1);
+        let e = parse_sensors("").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 1: expected a sensor, found end of input"
+        );
+    }
+
+    #[test]
+    fn test_compress_empty() {
+        let mut is: Intervals = vec![].into();
+        is.compress();
+        assert_eq!(is.interval_count(), 0);
+
+        let mut is: Intervals = vec![[1, 2].into(), [3, 4].into()].into();
+        is.compress();
+        assert_eq!(is.iter().collect::<Vec<_>>(), [&Interval::new(1, 4)]);
+    }
+
     #[test]
     fn interval_span() {
-        let mut is: Intervals = vec![[-4, 2].into(), [10, 13].into()].into();
+        let is: Intervals = vec![[-4, 2].into(), [10, 13].into()].into();
         assert_eq!(is.span(), 11);
 
-        let mut is: Intervals = vec![].into();
+        let is: Intervals = vec![].into();
         assert_eq!(is.span(), 0);
 
-        let mut is: Intervals = vec![[2, 2].into()].into();
+        let is: Intervals = vec![[2, 2].into()].into();
         assert_eq!(is.span(), 1);
     }
 }
@@ -1,20 +1,29 @@
-use crate::PuzzleRun;
-use nom::AsBytes;
-use petgraph::{
-    algo::BoundedMeasure,
-    data::DataMap,
-    graph::{EdgeReference, Graph, NodeIndex, UnGraph},
-    visit::{Data, EdgeRef, GraphBase, IntoEdgesDirected, NodeIndexable},
-    Direction, IntoWeightedEdge,
-};
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError, Token};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::bail;
+use rand::seq::SliceRandom;
+use rand::Rng as _;
 use regex::Regex;
-use std::collections::HashMap;
-use tracing::{event, info, instrument, Level};
+use std::collections::BTreeSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+use std::sync::LazyLock;
+use tracing::{event, Level};
 
 pub(crate) fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
     vec![Box::new(Part1)]
 }
 
+inventory::submit! {
+    crate::Day {
+        number: 16,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Valve {
     name: [u8; 2],
@@ -34,415 +43,290 @@ impl Valve {
     }
 }
 
-#[derive(Debug)]
-struct ValveParseError {
-    msg: String,
-}
-
-impl std::str::FromStr for Valve {
-    type Err = ValveParseError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re = Regex::new(
-            r"Valve ([[:alpha:]]{2}) has flow rate=(\d+); tunnels? leads? to valves? (.+)$",
-        )
-        .unwrap();
-        match re.captures(s).map(|c| c.extract()) {
-            Some((_, [name, rate, tunnels])) => Ok(Valve::new(
-                &name.as_bytes()[0..2],
-                u32::from_str(rate).unwrap(),
-                tunnels
-                    .split(',')
-                    .map(|s| {
-                        let mut a: [u8; 2] = Default::default();
-                        a.clone_from_slice(&s.trim()[0..2].as_bytes());
-                        a
-                    })
-                    .collect(),
-            )),
-            None => Err(ValveParseError {
-                msg: format!("failed to parse: {}", s),
-            }),
-        }
-    }
-}
-
-impl core::fmt::Display for ValveParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.msg)
-    }
-}
-
-type NodeId = <Graph<ValveState, ()> as GraphBase>::NodeId;
-type CostType = HashMap<(NodeId, NodeId), u32>;
+/// The most valves with a flow rate the search takes on.  Its states grow
+/// with the subsets of them open, and past this it runs for minutes; the
+/// puzzle input has 15.
+const MAX_RATED: usize = 20;
 
-/*
-#[derive(Clone, Debug)]
-struct WorldState<'a, K>
-where
-    K: BoundedMeasure,
-{
-    graph: Graph<&'a str, ()>,
-    costs: CostType<'a, K>,
-}
-*/
-
-#[derive(Debug)]
-enum PathStep {
-    OpenValve {
-        time: u32,
-        rate: u32,
-        flow_at_this_step: u32,
-        valve_opened: NodeId,
-        next: Box<PathStep>,
-    },
-    StepTo {
-        time: u32,
-        flow_at_this_step: u32,
-        step_to: NodeId,
-        next: Box<PathStep>,
-    },
-    Complete,
+static VALVE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^Valve ([[:alpha:]]{2}) has flow rate=(\d+); tunnels? leads? to valves? (.+)$")
+        .unwrap()
+});
+
+/// Parse a valve, along with the tokens naming its tunnels so that callers
+/// can point at one that leads nowhere.
+fn parse_valve<'a>(line: &Line<'a>) -> Result<(Valve, Vec<Token<'a>>), ScanError> {
+    let token = line.token();
+    let Some(captures) = VALVE.captures(token.text) else {
+        return Err(
+            token.error("expected `Valve XX has flow rate=N; tunnels lead to valves YY, ZZ`")
+        );
+    };
+    let (name, rate, tunnels) = (
+        &captures[1],
+        captures.get(2).unwrap(),
+        captures.get(3).unwrap(),
+    );
+    let rate = rate
+        .as_str()
+        .parse()
+        .map_err(|e| Line::from(token).error_at(rate.start(), format!("bad flow rate: {e}")))?;
+    let tunnels = Line::from(Token {
+        column: token.column + tunnels.start(),
+        text: tunnels.as_str(),
+        ..token
+    })
+    .split(",");
+    let targets = tunnels
+        .iter()
+        .map(|t| {
+            <[u8; 2]>::try_from(t.text.as_bytes())
+                .map_err(|_| t.error(format!("bad tunnel target `{}`", t.text)))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((Valve::new(name.as_bytes(), rate, targets), tunnels))
 }
 
-impl PathStep {
-    fn flow_at_completion(&self) -> u32 {
-        let mut flow: u32 = 0;
-        let mut step = self;
-        loop {
-            match step {
-                PathStep::Complete => return flow,
-                PathStep::StepTo {
-                    time,
-                    step_to,
-                    flow_at_this_step,
-                    next,
-                } => {
-                    flow += flow_at_this_step;
-                    step = next;
-                }
-                PathStep::OpenValve {
-                    time,
-                    flow_at_this_step,
-                    rate,
-                    valve_opened,
-                    next,
-                } => {
-                    flow += flow_at_this_step;
-                    step = next;
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let mut problems = vec![];
+    let mut valves = vec![];
+    let mut rated = 0;
+    for line in Scanner::new(input).lines() {
+        match parse_valve(&line) {
+            Ok(valve) => {
+                if valve.0.rate > 0 {
+                    rated += 1;
+                    if rated == MAX_RATED + 1 {
+                        problems.push(
+                            line.error(format!("more than {MAX_RATED} valves have a flow rate")),
+                        );
+                    }
                 }
+                valves.push(valve)
             }
+            Err(e) => problems.push(e),
         }
-        flow
     }
-
-    fn dump(&self) {
-        match self {
-            PathStep::Complete => {
-                println!("completed");
-            }
-            PathStep::StepTo {
-                time,
-                flow_at_this_step,
-                step_to,
-                next,
-            } => {
-                println!(" move to {:?}", step_to);
-                next.dump();
-            }
-            PathStep::OpenValve {
-                time,
-                flow_at_this_step,
-                rate,
-                valve_opened,
-                next,
-            } => {
-                println!("opened valve at {:?} rate {}", valve_opened, rate,);
-                next.dump();
-            }
-        }
+    let names: HashSet<[u8; 2]> = valves.iter().map(|(v, _)| v.name).collect();
+    if !names.contains(b"AA") {
+        problems.push(ScanError::new(1, 1, "no valve named AA to start from"));
     }
-}
-
-fn init_valves<'a, T: Iterator<Item = &'a str>>(input: T) -> HashMap<[u8; 2], Valve> {
-    input
-        .map(|s| {
-            let v = s.parse::<Valve>()?;
-            Ok((v.name.clone(), v))
-        })
-        .collect::<Result<HashMap<[u8; 2], Valve>, ValveParseError>>()
-        .unwrap()
-}
-
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
-struct ValveState {
-    name: [u8; 2],
-    rate: u32,
-    opened_time: u32,
-}
-
-impl ValveState {
-    fn init(valve: &Valve) -> Self {
-        Self {
-            name: valve.name,
-            rate: valve.rate,
-            opened_time: 0,
+    for (valve, tunnels) in &valves {
+        for (target, token) in valve.tunnels.iter().zip(tunnels) {
+            if !names.contains(target) {
+                problems.push(token.error(format!("tunnel to unknown valve {}", token.text)));
+            }
         }
     }
+    problems
 }
 
-impl std::fmt::Display for ValveState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "ValveState [at: {}, rate: {}, opened: {}",
-            std::str::from_utf8(self.name.as_bytes()).unwrap(),
-            self.rate,
-            self.opened_time
-        )
-    }
-}
-
-#[derive(Clone, Debug)]
-struct WorldState {
-    at: NodeId,
-    valves: HashMap<NodeId, ValveState>,
-}
-
-impl WorldState {
-    fn init<G>(start_from: NodeId, graph: &G, nodes: &HashMap<(NodeId, NodeId), u32>) -> Self
-    where
-        G: NodeIndexable + DataMap,
-        G: GraphBase<NodeId = NodeIndex>,
-        G: Data<NodeWeight = ValveState>,
-    {
-        Self {
-            at: start_from,
-            valves: nodes
-                .keys()
-                .flat_map(|(f, t)| {
-                    vec![
-                        (*f, *graph.node_weight(*f).unwrap()),
-                        (*t, *graph.node_weight(*t).unwrap()),
-                    ]
-                })
-                .collect(),
-        }
+/// A connected network of `scale` valves, up to the 676 that two-letter names
+/// allow.  About a third of them have a flow rate, up to the `MAX_RATED` the
+/// search takes on; `AA` never does.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let count = scale.clamp(2, 26 * 26);
+    let mut names: Vec<[u8; 2]> = (b'A'..=b'Z')
+        .flat_map(|a| (b'A'..=b'Z').map(move |b| [a, b]))
+        .skip(1)
+        .collect();
+    names.shuffle(rng);
+    names.insert(0, *b"AA");
+    names.truncate(count);
+
+    // a random spanning tree keeps every valve reachable, then some shortcuts
+    let mut tunnels: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for i in 1..count {
+        tunnels.insert((rng.gen_range(0..i), i));
     }
-    fn turn_on_current_valve(&self, time: u32) -> Self {
-        let mut new_w = self.clone();
-        let v = new_w.valves.get_mut(&self.at).unwrap();
-        if v.opened_time > 0 {
-            panic!();
+    for _ in 0..count / 2 {
+        let (a, b) = (rng.gen_range(0..count), rng.gen_range(0..count));
+        if a != b {
+            tunnels.insert((a.min(b), a.max(b)));
         }
-        v.opened_time = time;
-        new_w
-    }
-
-    fn take_tunnel(&self, to: NodeId) -> Self {
-        let mut new_w = self.clone();
-        new_w.at = to;
-        new_w
-    }
-
-    fn get_valve(&self, id: NodeId) -> ValveState {
-        *self.valves.get(&id).unwrap()
     }
-
-    fn get_current_valve(&self) -> ValveState {
-        self.get_valve(self.at)
+    let mut neighbors: Vec<Vec<&str>> = vec![vec![]; count];
+    for &(a, b) in tunnels.iter() {
+        neighbors[a].push(std::str::from_utf8(&names[b]).unwrap());
+        neighbors[b].push(std::str::from_utf8(&names[a]).unwrap());
     }
 
-    fn is_complete(&self) -> bool {
-        self.valves
-            .values()
-            .all(|v| v.rate == 0 || v.opened_time > 0)
-    }
-
-    fn flow_per_step(&self) -> u32 {
-        self.valves.values().map(|v| v.rate as u32).sum()
+    let mut out = String::new();
+    let mut rated = 0;
+    for (i, name) in names.iter().enumerate() {
+        let rate = match i {
+            0 => 0,
+            _ if rated < MAX_RATED && rng.gen_bool(0.3) => {
+                rated += 1;
+                rng.gen_range(1..=25)
+            }
+            _ => 0,
+        };
+        let name = std::str::from_utf8(name).unwrap();
+        let leads = match neighbors[i].len() {
+            1 => "tunnel leads to valve",
+            _ => "tunnels lead to valves",
+        };
+        writeln!(
+            out,
+            "Valve {name} has flow rate={rate}; {leads} {}",
+            neighbors[i].join(", ")
+        )
+        .unwrap();
     }
+    out
 }
 
-fn has_tunnel(graph: &Graph<ValveState, ()>, from: NodeId, to: NodeId) -> bool {
-    graph.contains_edge(
-        NodeIndex::new(graph.to_index(from)),
-        NodeIndex::new(graph.to_index(to)),
-    )
-}
-
-fn get_tunnels_from(
-    graph: &Graph<ValveState, ()>,
-    costs: &CostType,
-    from: NodeId,
-) -> Vec<(NodeId, u32)> {
-    costs
-        .iter()
-        .filter_map(|((f, t), cost)| {
-            if *f == from && *t != from {
-                Some((*t, *cost))
-            } else {
-                None
-            }
+fn init_valves(input: &str) -> anyhow::Result<HashMap<[u8; 2], Valve>> {
+    Scanner::new(input)
+        .lines()
+        .map(|line| {
+            let (v, _) = parse_valve(&line)?;
+            Ok((v.name, v))
         })
         .collect()
 }
 
-fn best(
-    graph: &Graph<ValveState, ()>,
-    costs: &CostType,
-    world_state: WorldState,
-    time_at_start: u32,
-    time_limit: u32,
-) -> Option<PathStep> {
-    if time_at_start >= time_limit {
-        return if world_state.is_complete() {
-            Some(PathStep::Complete)
-        } else {
-            None
-        };
-    }
-    event!(Level::INFO, time = time_at_start, at = %graph[world_state.at]);
-
-    let mut best_value = std::u32::MIN;
-    let mut best_step: Option<PathStep> = None;
+/// The valves worth opening, and the minutes it takes to walk between them
+/// and from `AA`.
+struct Network {
+    rates: Vec<u32>,
+    names: Vec<[u8; 2]>,
+    /// `dist[i][j]` between useful valves; the start is index `rates.len()`.
+    dist: Vec<Vec<u32>>,
+}
 
-    let this_valve = world_state.get_current_valve();
-    if this_valve.opened_time == 0 && this_valve.rate > 0 {
-        let next = world_state.turn_on_current_valve(time_at_start + 1);
-        if let Some(step) = best(graph, costs, next, time_at_start + 1, time_limit) {
-            event!(Level::INFO, "turning on valve at {}", this_valve);
-            let v = world_state.get_current_valve();
-            best_value = step.flow_at_completion();
-            best_step.replace(PathStep::OpenValve {
-                time: time_at_start + 1,
-                flow_at_this_step: world_state.flow_per_step(),
-                rate: v.rate,
-                valve_opened: world_state.at,
-                next: Box::new(step),
-            });
+impl Network {
+    fn new(valves: &HashMap<[u8; 2], Valve>) -> anyhow::Result<Self> {
+        if !valves.contains_key(b"AA") {
+            bail!("no valve named AA to start from");
+        }
+        for v in valves.values() {
+            if let Some(t) = v.tunnels.iter().find(|t| !valves.contains_key(*t)) {
+                bail!(
+                    "valve {} has a tunnel to unknown valve {}",
+                    String::from_utf8_lossy(&v.name),
+                    String::from_utf8_lossy(t)
+                );
+            }
         }
+        let mut useful: Vec<&Valve> = valves.values().filter(|v| v.rate > 0).collect();
+        useful.sort_by_key(|v| v.name);
+        if useful.len() > MAX_RATED {
+            bail!(
+                "{} valves have a flow rate; the search handles at most {MAX_RATED}",
+                useful.len()
+            );
+        }
+        let mut names: Vec<[u8; 2]> = useful.iter().map(|v| v.name).collect();
+        names.push(*b"AA");
+        let dist = names
+            .iter()
+            .map(|from| {
+                let steps = Self::walk(valves, *from);
+                names
+                    .iter()
+                    .map(|to| steps.get(to).copied().unwrap_or(u32::MAX))
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            rates: useful.iter().map(|v| v.rate).collect(),
+            names,
+            dist,
+        })
     }
 
-    //self.print_debug(level);
-    //println!("[{:p}] {:?}", self, self);
-    for (t, cost) in get_tunnels_from(graph, costs, world_state.at) {
-        event!(
-            Level::INFO,
-            "tring a move from {} to {}",
-            graph[world_state.at],
-            graph[t]
-        );
-        let next = world_state.take_tunnel(t);
-        if let Some(step) = best(graph, costs, next, time_at_start + cost as u32, time_limit) {
-            let this_flow = step.flow_at_completion();
-            if this_flow > best_value {
-                best_step.replace(PathStep::StepTo {
-                    time: time_at_start + cost,
-                    flow_at_this_step: world_state.flow_per_step(),
-                    step_to: t.clone(),
-                    next: Box::new(step),
-                });
-                best_value = this_flow;
+    /// Minutes from `from` to every valve it can reach.
+    fn walk(valves: &HashMap<[u8; 2], Valve>, from: [u8; 2]) -> HashMap<[u8; 2], u32> {
+        let mut steps = HashMap::from([(from, 0)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(at) = queue.pop_front() {
+            let n = steps[&at];
+            for t in valves[&at].tunnels.iter() {
+                if !steps.contains_key(t) {
+                    steps.insert(*t, n + 1);
+                    queue.push_back(*t);
+                }
             }
         }
+        steps
     }
 
-    if best_step.is_some() {
-        best_step
-    } else if world_state.is_complete() {
-        Some(PathStep::Complete)
-    } else {
-        None
+    /// The most pressure released from `at` with `time` minutes left and
+    /// the valves in `opened` already open.  Results are memoized on all
+    /// three, which bounds the search by the states it can reach.
+    fn best(
+        &self,
+        at: usize,
+        time: u32,
+        opened: u64,
+        memo: &mut HashMap<(usize, u32, u64), u32>,
+    ) -> u32 {
+        if let Some(&known) = memo.get(&(at, time, opened)) {
+            return known;
+        }
+        let mut best = 0;
+        for (next, &rate) in self.rates.iter().enumerate() {
+            let bit = 1 << next;
+            // walking there and opening it takes a minute more than the walk
+            let cost = self.dist[at][next].saturating_add(1);
+            if opened & bit != 0 || cost >= time {
+                continue;
+            }
+            let left = time - cost;
+            let released = rate * left + self.best(next, left, opened | bit, memo);
+            if released > best {
+                event!(
+                    Level::TRACE,
+                    "open {} with {left} minutes left for {released}",
+                    String::from_utf8_lossy(&self.names[next])
+                );
+                best = released;
+            }
+        }
+        memo.insert((at, time, opened), best);
+        best
     }
 }
 
 struct Part1;
 
-fn test_data() -> &'static str {
-    "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-    Valve BB has flow rate=13; tunnels lead to valves CC, AA
-    Valve CC has flow rate=2; tunnels lead to valves DD, BB
-    Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-    Valve EE has flow rate=3; tunnels lead to valves FF, DD
-    Valve FF has flow rate=0; tunnels lead to valves EE, GG
-    Valve GG has flow rate=0; tunnels lead to valves FF, HH
-    Valve HH has flow rate=22; tunnel leads to valve GG
-    Valve II has flow rate=0; tunnels lead to valves AA, JJ
-    Valve JJ has flow rate=21; tunnel leads to valve II"
-}
-
-fn simple_test_data() -> &'static str {
-    "Valve AA has flow rate=0; tunnels lead to valves BB, CC
-    Valve BB has flow rate=13; tunnel leads to valves CC
-    Valve CC has flow rate=2; tunnel leads to valve AA"
-}
-
-fn vec_set(v: &mut Vec<u64>, row_size: usize, i: usize, j: usize, value: u64) {
-    v[i * row_size + j] = value;
-}
-
 impl PuzzleRun for Part1 {
-    fn input_data(&self) -> anyhow::Result<&str> {
-        Ok(simple_test_data())
+    fn part(&self) -> u8 {
+        1
     }
 
-    fn run(&self, input: &str) -> String {
-        let valves = init_valves(input.lines());
-        let mut graph = Graph::<ValveState, ()>::new();
-
-        let nodes: HashMap<[u8; 2], NodeIndex<_>> = valves
-            .iter()
-            .map(|(k, v)| (*k, graph.add_node(ValveState::init(v))))
-            .collect();
-
-        let start_node: NodeId = graph.from_index(nodes.get(b"AA").unwrap().index());
+    fn description(&self) -> &str {
+        "most pressure that can be released in 30 minutes"
+    }
 
-        graph.extend_with_edges(nodes.iter().flat_map(|(id, &from_node)| {
-            let nodes = &nodes;
-            valves
-                .get(id)
-                .unwrap()
-                .tunnels
-                .iter()
-                .map(move |t| (from_node, *nodes.get(t).unwrap()))
-            //                    .map(move |t| (from_node, map_edge(t.as_str(), nodes)))
-        }));
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d16/input.txt")
+    }
 
-        let mut costs: HashMap<_, _> = petgraph::algo::floyd_warshall(&graph, |e| 1).unwrap();
-        costs.retain(|(from, to), _| {
-            *from == start_node || (graph[*from].rate > 0 && graph[*to].rate > 0)
-        });
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
 
-        match best(
-            &graph,
-            &costs,
-            WorldState::init(start_node, &graph, &costs),
-            0,
-            30,
-        ) {
-            Some(path) => {
-                path.dump();
-                "OK".to_string()
-            }
-            None => "failed".to_string(),
-        }
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(Network::new(&init_valves(input)?)?))
     }
-}
 
-// delete
-fn map_edge<'a, E>(to_node: &'a str, nodes: &'a HashMap<&str, NodeIndex<E>>) -> &'a NodeIndex<E> {
-    nodes.get(to_node).unwrap()
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let network: Network = parsed.take();
+        let start = network.rates.len();
+        let released = network.best(start, 30, 0, &mut HashMap::new());
+        Ok(released.into())
+    }
 }
 
 #[cfg(test)]
 mod test {
-
     use super::*;
     use regex::Regex;
 
-    fn setup() {
-        tracing_subscriber::fmt::init();
-    }
+    example_tests!(16: 1651);
 
     #[test]
     fn test_parse() {
@@ -450,22 +334,46 @@ mod test {
         let re_str = r"Valve ([[:alpha:]]{2}) has flow rate=(\d+); tunnels? leads? to valves?";
 
         let re = Regex::new(re_str).unwrap();
-        for l in super::test_data().lines() {
-            match re.captures(l) {
-                Some(c) => {
-                    println!("MATCHED: {:?}", c);
-                }
-                None => {
-                    println!("FAIL: {}", l);
-                }
-            }
+        for l in include_str!("example.txt").lines() {
+            assert!(re.is_match(l), "no match: {l}");
         }
     }
 
+    #[test]
+    fn test_rated_limit() {
+        let generated = crate::generate::input(16, 0, 500).unwrap();
+        assert_eq!(
+            generated.lines().filter(|l| !l.contains("rate=0;")).count(),
+            MAX_RATED
+        );
+        assert!(validate(&generated).is_empty());
+
+        // a chain of valves AA, AB, ... each with a flow rate, one too many
+        let names: Vec<String> = (b'A'..)
+            .take(MAX_RATED + 1)
+            .map(|c| format!("A{}", c as char))
+            .collect();
+        let mut input = String::new();
+        for (i, name) in names.iter().enumerate() {
+            let next = &names[(i + 1) % names.len()];
+            writeln!(
+                input,
+                "Valve {name} has flow rate=1; tunnel leads to valve {next}"
+            )
+            .unwrap();
+        }
+        let problems = validate(&input);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, MAX_RATED + 1);
+        assert!(Part1.run(&input).is_err());
+    }
+
     #[test]
     fn test_part1() {
-        setup();
         let p1 = Part1;
-        p1.run(p1.input_data().unwrap());
+        assert_eq!(
+            p1.run(&p1.input_data().unwrap()).unwrap(),
+            Answer::Int(1871)
+        );
     }
 }
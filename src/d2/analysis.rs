@@ -0,0 +1,185 @@
+use super::game::{Game, Move, Outcome, Round};
+
+/// One response to an opponent's move and what it scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub(crate) ours: Move,
+    pub(crate) outcome: Outcome,
+    pub(crate) score: u64,
+}
+
+/// The best and worst we can do against one of the opponent's moves.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RoundAnalysis {
+    pub(crate) theirs: Move,
+    /// The highest-scoring response; of equal scores, the first defined.
+    pub(crate) best: Response,
+    /// The lowest-scoring response; of equal scores, the first defined.
+    pub(crate) worst: Response,
+    /// Every response's score, in move order.
+    scores: Vec<u64>,
+}
+
+impl RoundAnalysis {
+    fn new(game: &Game, theirs: Move) -> Self {
+        let responses: Vec<Response> = game
+            .moves()
+            .map(|ours| Response {
+                ours,
+                outcome: game.outcome(theirs, ours),
+                score: game.score(Round { theirs, ours }),
+            })
+            .collect();
+        let best = *responses
+            .iter()
+            .rev()
+            .max_by_key(|r| r.score)
+            .expect("a game has at least one move");
+        let worst = *responses
+            .iter()
+            .min_by_key(|r| r.score)
+            .expect("a game has at least one move");
+        Self {
+            theirs,
+            best,
+            worst,
+            scores: responses.iter().map(|r| r.score).collect(),
+        }
+    }
+
+    /// Why the best response was chosen, e.g. `against Scissors, Rock is a
+    /// win for 1 + 6 = 7; worst is Paper, a loss for 2 + 0 = 2`.
+    pub(crate) fn explain(&self, game: &Game) -> String {
+        let points = |r: &Response| {
+            format!(
+                "{} + {} = {}",
+                game.move_score(r.ours),
+                game.outcome_score(r.outcome),
+                r.score
+            )
+        };
+        format!(
+            "against {}, {} is a {} for {}; worst is {}, a {} for {}",
+            game.name(self.theirs),
+            game.name(self.best.ours),
+            self.best.outcome,
+            points(&self.best),
+            game.name(self.worst.ours),
+            self.worst.outcome,
+            points(&self.worst)
+        )
+    }
+}
+
+/// What a sequence of opponent moves allows: the best and worst totals,
+/// and how the total falls for a responder who picks uniformly at random.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Analysis {
+    pub(crate) rounds: Vec<RoundAnalysis>,
+    pub(crate) max: u64,
+    pub(crate) min: u64,
+    /// `distribution[i]` is the chance a random responder totals `min + i`.
+    distribution: Vec<f64>,
+}
+
+impl Analysis {
+    pub(crate) fn new(game: &Game, theirs: impl IntoIterator<Item = Move>) -> Self {
+        let rounds: Vec<RoundAnalysis> = theirs
+            .into_iter()
+            .map(|m| RoundAnalysis::new(game, m))
+            .collect();
+        let max = rounds.iter().map(|r| r.best.score).sum();
+        let min = rounds.iter().map(|r| r.worst.score).sum();
+
+        // convolve in each round's scores, keeping totals relative to the
+        // worst so far
+        let mut distribution = vec![1.0];
+        for round in rounds.iter() {
+            let width = (round.best.score - round.worst.score) as usize;
+            let p = 1.0 / round.scores.len() as f64;
+            let mut next = vec![0.0; distribution.len() + width];
+            for (i, q) in distribution.iter().enumerate() {
+                for score in round.scores.iter() {
+                    next[i + (score - round.worst.score) as usize] += q * p;
+                }
+            }
+            distribution = next;
+        }
+        Self {
+            rounds,
+            max,
+            min,
+            distribution,
+        }
+    }
+
+    /// Each total a random responder can reach, with its probability.
+    pub(crate) fn distribution(&self) -> impl Iterator<Item = (u64, f64)> + '_ {
+        (self.min..)
+            .zip(self.distribution.iter().copied())
+            .filter(|(_, p)| *p > 0.0)
+    }
+
+    /// The total a random responder scores on average.
+    pub(crate) fn mean(&self) -> f64 {
+        self.distribution().map(|(s, p)| s as f64 * p).sum()
+    }
+
+    /// The lowest total a random responder reaches at least a `q` share of
+    /// the time, for `q` from 0 to 1.
+    pub(crate) fn quantile(&self, q: f64) -> u64 {
+        let mut seen = 0.0;
+        for (score, p) in self.distribution() {
+            seen += p;
+            // allow for rounding in the sum
+            if seen >= q - 1e-9 {
+                return score;
+            }
+        }
+        self.max
+    }
+
+    /// The chance a random responder totals less than `score`.
+    pub(crate) fn below(&self, score: u64) -> f64 {
+        self.distribution()
+            .take_while(|(s, _)| *s < score)
+            .map(|(_, p)| p)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::d2::game::Strategy;
+
+    #[test]
+    fn test_example() {
+        let game = Game::rock_paper_scissors();
+        let theirs: Vec<Move> = game
+            .parse_guide(include_str!("example.txt"), Strategy::Moves)
+            .unwrap()
+            .iter()
+            .map(|r| r.theirs)
+            .collect();
+        let analysis = Analysis::new(&game, theirs);
+        assert_eq!((analysis.min, analysis.max), (6, 24));
+        assert_eq!(
+            analysis.rounds[2].explain(&game),
+            "against Scissors, Rock is a win for 1 + 6 = 7; worst is Paper, a loss for 2 + 0 = 2"
+        );
+
+        let total: f64 = analysis.distribution().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!((analysis.mean() - 15.0).abs() < 1e-9);
+        // one in 27 responses is the worst in every round
+        let (worst, p) = analysis.distribution().next().unwrap();
+        assert_eq!(worst, 6);
+        assert!((p - 1.0 / 27.0).abs() < 1e-9);
+        assert_eq!(analysis.quantile(0.0), 6);
+        assert_eq!(analysis.quantile(0.5), 15);
+        assert_eq!(analysis.quantile(1.0), 24);
+        assert_eq!(analysis.below(6), 0.0);
+        assert!((analysis.below(25) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::scanner::{Line, ScanError, Scanner, Token};
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+
+/// How a round ends, from our side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+}
+
+impl std::str::FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "loss" => Ok(Outcome::Loss),
+            "draw" => Ok(Outcome::Draw),
+            "win" => Ok(Outcome::Win),
+            _ => Err("expected loss, draw or win".to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Outcome::Loss => "loss",
+            Outcome::Draw => "draw",
+            Outcome::Win => "win",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A move, as its index among the game's moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Move(usize);
+
+/// How to read the second column of a strategy guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Strategy {
+    /// The move to play.
+    Moves,
+    /// The outcome to reach.
+    Outcomes,
+}
+
+/// One round of a guide: what they play and what we play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Round {
+    pub(crate) theirs: Move,
+    pub(crate) ours: Move,
+}
+
+/// A game in the family of Rock-Paper-Scissors: both players pick a move,
+/// and whoever picked the move that beats the other's wins.  We score the
+/// points for the move we picked plus the points for the outcome.
+///
+/// A game is loaded from a definition with one directive per line; blank
+/// lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// move <name> <score>           a move and the points for playing it
+/// beats <name> <name>...        the first move beats each of the others
+/// outcome <loss|draw|win> <n>   the points for an outcome
+/// theirs <symbol> <name>        a symbol for the opponent's move
+/// ours <symbol> <name>          read as a move to play, the move
+/// directs <symbol> <outcome>    read as an outcome to reach, the outcome
+/// ```
+///
+/// Every pair of distinct moves must have exactly one winner.
+#[derive(Debug, Clone)]
+pub(crate) struct Game {
+    names: Vec<String>,
+    scores: Vec<u64>,
+    /// `beats[a][b]` when move `a` beats move `b`.
+    beats: Vec<Vec<bool>>,
+    outcomes: BTreeMap<Outcome, u64>,
+    theirs: BTreeMap<String, Move>,
+    ours: BTreeMap<String, Move>,
+    directs: BTreeMap<String, Outcome>,
+}
+
+impl Game {
+    /// The puzzle's Rock-Paper-Scissors.
+    pub(crate) fn rock_paper_scissors() -> Self {
+        include_str!("rps.txt")
+            .parse()
+            .expect("the bundled game is valid")
+    }
+
+    pub(crate) fn moves(&self) -> impl Iterator<Item = Move> {
+        (0..self.names.len()).map(Move)
+    }
+
+    pub(crate) fn name(&self, m: Move) -> &str {
+        &self.names[m.0]
+    }
+
+    /// How the round ends for whoever played `ours`.
+    pub(crate) fn outcome(&self, theirs: Move, ours: Move) -> Outcome {
+        if self.beats[ours.0][theirs.0] {
+            Outcome::Win
+        } else if self.beats[theirs.0][ours.0] {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The points for playing `m`.
+    pub(crate) fn move_score(&self, m: Move) -> u64 {
+        self.scores[m.0]
+    }
+
+    /// The points for reaching `outcome`.
+    pub(crate) fn outcome_score(&self, outcome: Outcome) -> u64 {
+        self.outcomes[&outcome]
+    }
+
+    /// Our score for a round.
+    pub(crate) fn score(&self, round: Round) -> u64 {
+        self.move_score(round.ours) + self.outcome_score(self.outcome(round.theirs, round.ours))
+    }
+
+    /// The move that reaches `outcome` against `theirs`.  When several do,
+    /// the highest-scoring one is played, and of those the first defined.
+    pub(crate) fn respond(&self, theirs: Move, outcome: Outcome) -> Option<Move> {
+        self.moves()
+            .filter(|&ours| self.outcome(theirs, ours) == outcome)
+            .min_by_key(|&ours| std::cmp::Reverse(self.scores[ours.0]))
+    }
+
+    /// The symbols the opponent's column may hold.
+    pub(crate) fn their_symbols(&self) -> impl Iterator<Item = &str> {
+        self.theirs.keys().map(String::as_str)
+    }
+
+    /// The symbols our column may hold under every strategy.
+    pub(crate) fn our_symbols(&self) -> impl Iterator<Item = &str> {
+        self.ours
+            .keys()
+            .filter(|s| self.directs.contains_key(*s))
+            .map(String::as_str)
+    }
+
+    fn their_move(&self, token: &Token) -> Result<Move, ScanError> {
+        self.theirs
+            .get(token.text)
+            .copied()
+            .ok_or_else(|| token.error(format!("unknown move `{}`", token.text)))
+    }
+
+    fn our_move(&self, theirs: Move, token: &Token, strategy: Strategy) -> Result<Move, ScanError> {
+        match strategy {
+            Strategy::Moves => self
+                .ours
+                .get(token.text)
+                .copied()
+                .ok_or_else(|| token.error(format!("unknown move `{}`", token.text))),
+            Strategy::Outcomes => {
+                let outcome = self
+                    .directs
+                    .get(token.text)
+                    .ok_or_else(|| token.error(format!("unknown outcome `{}`", token.text)))?;
+                self.respond(theirs, *outcome).ok_or_else(|| {
+                    token.error(format!(
+                        "no move reaches a {outcome} against {}",
+                        self.name(theirs)
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Parse one line of a guide, reading our column by `strategy`.
+    pub(crate) fn parse_round(&self, line: &Line, strategy: Strategy) -> Result<Round, ScanError> {
+        let tokens: Vec<Token> = line.tokens().collect();
+        let [theirs, ours] = tokens.as_slice() else {
+            return Err(line.error("expected two choices separated by a space"));
+        };
+        let theirs_move = self.their_move(theirs)?;
+        Ok(Round {
+            theirs: theirs_move,
+            ours: self.our_move(theirs_move, ours, strategy)?,
+        })
+    }
+
+    /// The rounds of a guide, reading our column by `strategy`.
+    pub(crate) fn parse_guide(&self, input: &str, strategy: Strategy) -> Result<Vec<Round>> {
+        Ok(Scanner::new(input)
+            .lines()
+            .map(|line| self.parse_round(&line, strategy))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn move_named(&self, token: &Token) -> Result<Move, ScanError> {
+        self.names
+            .iter()
+            .position(|n| n == token.text)
+            .map(Move)
+            .ok_or_else(|| token.error(format!("no move named `{}`", token.text)))
+    }
+
+    fn directive(&mut self, line: &Line) -> Result<(), ScanError> {
+        let tokens: Vec<Token> = line.tokens().collect();
+        let arity = |n: usize| {
+            if tokens.len() == n {
+                Ok(())
+            } else {
+                Err(line.error(format!("`{}` takes {} arguments", tokens[0].text, n - 1)))
+            }
+        };
+        match tokens[0].text {
+            "move" => {
+                arity(3)?;
+                if self.move_named(&tokens[1]).is_ok() {
+                    return Err(tokens[1].error(format!("`{}` is defined twice", tokens[1].text)));
+                }
+                self.names.push(tokens[1].text.to_string());
+                self.scores.push(tokens[2].parse()?);
+                for row in self.beats.iter_mut() {
+                    row.push(false);
+                }
+                self.beats.push(vec![false; self.names.len()]);
+            }
+            "beats" => {
+                if tokens.len() < 3 {
+                    return Err(line.error("`beats` takes a move and the moves it beats"));
+                }
+                let winner = self.move_named(&tokens[1])?;
+                for token in tokens[2..].iter() {
+                    let loser = self.move_named(token)?;
+                    if winner == loser {
+                        return Err(token.error("a move can't beat itself"));
+                    }
+                    if self.beats[loser.0][winner.0] {
+                        return Err(token.error(format!(
+                            "`{}` already beats `{}`",
+                            token.text, tokens[1].text
+                        )));
+                    }
+                    self.beats[winner.0][loser.0] = true;
+                }
+            }
+            "outcome" => {
+                arity(3)?;
+                self.outcomes.insert(tokens[1].parse()?, tokens[2].parse()?);
+            }
+            "theirs" | "ours" => {
+                arity(3)?;
+                let m = self.move_named(&tokens[2])?;
+                let symbols = match tokens[0].text {
+                    "theirs" => &mut self.theirs,
+                    _ => &mut self.ours,
+                };
+                symbols.insert(tokens[1].text.to_string(), m);
+            }
+            "directs" => {
+                arity(3)?;
+                self.directs
+                    .insert(tokens[1].text.to_string(), tokens[2].parse()?);
+            }
+            other => return Err(tokens[0].error(format!("unknown directive `{other}`"))),
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Game {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut game = Game {
+            names: vec![],
+            scores: vec![],
+            beats: vec![],
+            outcomes: BTreeMap::new(),
+            theirs: BTreeMap::new(),
+            ours: BTreeMap::new(),
+            directs: BTreeMap::new(),
+        };
+        for line in Scanner::new(s).lines() {
+            let text = line.text.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            game.directive(&line).context("bad game definition")?;
+        }
+
+        if game.names.is_empty() {
+            return Err(anyhow!("a game needs at least one move"));
+        }
+        if let Some(outcome) = Outcome::ALL.iter().find(|o| !game.outcomes.contains_key(o)) {
+            return Err(anyhow!("no score for a {outcome}"));
+        }
+        for a in game.moves() {
+            for b in game.moves().filter(|&b| b > a) {
+                if !game.beats[a.0][b.0] && !game.beats[b.0][a.0] {
+                    return Err(anyhow!(
+                        "neither `{}` nor `{}` beats the other",
+                        game.name(a),
+                        game.name(b)
+                    ));
+                }
+            }
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rpsls() -> Game {
+        include_str!("rpsls.txt").parse().unwrap()
+    }
+
+    #[test]
+    fn test_outcomes() {
+        let game = rpsls();
+        let [rock, paper, scissors, lizard, spock] = [0, 1, 2, 3, 4].map(Move);
+        assert_eq!(game.outcome(rock, paper), Outcome::Win);
+        assert_eq!(game.outcome(spock, lizard), Outcome::Win);
+        assert_eq!(game.outcome(lizard, scissors), Outcome::Win);
+        assert_eq!(game.outcome(rock, scissors), Outcome::Loss);
+        assert_eq!(game.outcome(spock, spock), Outcome::Draw);
+        // paper and spock both beat rock; spock scores more
+        assert_eq!(game.respond(rock, Outcome::Win), Some(spock));
+        assert_eq!(game.respond(rock, Outcome::Draw), Some(rock));
+    }
+
+    #[test]
+    fn test_guide() {
+        let game = rpsls();
+        let total = |strategy| -> u64 {
+            game.parse_guide("A Z\nE Y\n", strategy)
+                .unwrap()
+                .into_iter()
+                .map(|r| game.score(r))
+                .sum()
+        };
+        // scissors loses to rock for 3 + 0; paper disproves spock for 2 + 6
+        assert_eq!(total(Strategy::Moves), 11);
+        // spock wins against rock for 5 + 6; spock draws with spock for 5 + 3
+        assert_eq!(total(Strategy::Outcomes), 19);
+
+        assert!(game.parse_guide("A W\n", Strategy::Moves).is_ok());
+        let e = game.parse_guide("A W\n", Strategy::Outcomes).unwrap_err();
+        assert_eq!(e.to_string(), "line 1, column 3: unknown outcome `W`");
+        assert_eq!(game.our_symbols().collect::<Vec<_>>(), ["X", "Y", "Z"]);
+    }
+
+    #[test]
+    fn test_bad_definitions() {
+        let base = "move a 1\nmove b 2\noutcome loss 0\noutcome draw 3\noutcome win 6\n";
+        assert!(format!("{base}beats a b\n").parse::<Game>().is_ok());
+
+        let e = base.parse::<Game>().unwrap_err();
+        assert_eq!(e.to_string(), "neither `a` nor `b` beats the other");
+        let e = format!("{base}beats a b\nbeats b a\n")
+            .parse::<Game>()
+            .unwrap_err();
+        assert_eq!(
+            format!("{e:#}"),
+            "bad game definition: line 7, column 9: `a` already beats `b`"
+        );
+        let e = format!("{base}beats a c\n").parse::<Game>().unwrap_err();
+        assert_eq!(
+            format!("{e:#}"),
+            "bad game definition: line 6, column 9: no move named `c`"
+        );
+        assert!("move a 1\noutcome win 1\n".parse::<Game>().is_err());
+    }
+}
@@ -1,159 +1,258 @@
-use crate::PuzzleRun;
-use std::io::BufRead;
+use crate::generate::Rng;
+use crate::scanner::ScanError;
+use crate::{Answer, Parsed, PuzzleRun};
+use anyhow::Result;
+use rand::Rng as _;
+use std::fmt::Write;
+use tracing::{enabled, event, Level};
 
-#[derive(Debug, Copy, Clone)]
+use analysis::Analysis;
+use game::{Game, Round, Strategy};
 
-struct Part1;
+mod analysis;
+mod game;
 
-struct Part2;
+/// Each part plays `game`, which is the puzzle's Rock-Paper-Scissors unless
+/// the parts come from `game_runs`.
+struct Part1 {
+    game: Game,
+}
+
+struct Part2 {
+    game: Game,
+}
 
 pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
-    vec![Box::new(Part1), Box::new(Part2)]
-}
-#[derive(Debug, Clone, Copy)]
-enum Choice1 {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl Choice1 {
-    fn score(self, c: Choice2) -> u64 {
-        println!("{:?} scoring {:?}", self, c);
-        match self {
-            Choice1::Rock => match c {
-                // rock
-                Choice2::Rock => 1 + 3,
-                Choice2::Paper => 2 + 6, // paper beats rock
-                Choice2::Scissors => 3 + 0,
-            },
-            Choice1::Paper => match c {
-                // paper
-                Choice2::Rock => 1 + 0,
-                Choice2::Paper => 2 + 3,
-                Choice2::Scissors => 3 + 6, // scossors beat paper
-            },
-            Choice1::Scissors => match c {
-                Choice2::Rock => 1 + 6, // rock beats scissors
-                Choice2::Paper => 2 + 0,
-                Choice2::Scissors => 3 + 3,
-            },
-        }
-    }
+    runs(Game::rock_paper_scissors())
+}
 
-    fn loose(&self) -> Choice2 {
-        match self {
-            Self::Rock => Choice2::Paper,
-            Self::Paper => Choice2::Scissors,
-            Self::Scissors => Choice2::Rock,
-        }
-    }
+/// The parts, playing the game that `definition` describes; see `Game` for
+/// the format.
+pub(crate) fn game_runs(definition: &str) -> Result<Vec<Box<dyn PuzzleRun>>> {
+    Ok(runs(definition.parse()?))
+}
 
-    fn draw(&self) -> Choice2 {
-        match self {
-            Self::Rock => Choice2::Rock,
-            Self::Paper => Choice2::Paper,
-            Self::Scissors => Choice2::Scissors,
-        }
-    }
+fn runs(game: Game) -> Vec<Box<dyn PuzzleRun>> {
+    vec![
+        Box::new(Part1 { game: game.clone() }),
+        Box::new(Part2 { game }),
+    ]
+}
 
-    fn win(&self) -> Choice2 {
-        match self {
-            Self::Rock => Choice2::Scissors,
-            Self::Paper => Choice2::Rock,
-            Self::Scissors => Choice2::Paper,
-        }
+inventory::submit! {
+    crate::Day {
+        number: 2,
+        runs: get_runs,
+        validate,
+        generate,
     }
 }
 
-impl TryFrom<char> for Choice1 {
-    type Error = &'static str;
+/// Our total score for following the guide, reading our column by
+/// `strategy`.
+fn score_guide(game: &Game, input: &str, strategy: Strategy) -> Result<u64> {
+    Ok(score_rounds(game, &game.parse_guide(input, strategy)?))
+}
 
-    fn try_from(v: char) -> Result<Self, Self::Error> {
-        match v {
-            'A' => Ok(Choice1::Rock),
-            'B' => Ok(Choice1::Paper),
-            'C' => Ok(Choice1::Scissors),
-            _ => Err("Invalid choice"),
+/// Our total score for playing `rounds`.  With debug logging on, the score
+/// is compared with the best and worst possible and with random play.
+fn score_rounds(game: &Game, rounds: &[Round]) -> u64 {
+    let score = rounds
+        .iter()
+        .map(|round| {
+            let score = game.score(*round);
+            event!(
+                Level::TRACE,
+                "{} against {}: {score}",
+                game.name(round.ours),
+                game.name(round.theirs)
+            );
+            score
+        })
+        .sum();
+
+    if enabled!(Level::DEBUG) {
+        let analysis = Analysis::new(game, rounds.iter().map(|r| r.theirs));
+        for (n, round) in analysis.rounds.iter().enumerate() {
+            event!(Level::TRACE, "round {}: {}", n + 1, round.explain(game));
         }
+        event!(
+            Level::DEBUG,
+            "guide scores {score} of {} to {}; random play averages {:.1} and scores less {:.1}% of the time",
+            analysis.min,
+            analysis.max,
+            analysis.mean(),
+            100.0 * analysis.below(score)
+        );
     }
+    score
 }
 
-#[derive(Debug, Copy, Clone)]
-enum Choice2 {
-    Rock,     // loose
-    Paper,    // draw
-    Scissors, // win
+/// The guide's rounds in `game`, reading our column by `strategy`.
+fn parse(game: &Game, input: &str, strategy: Strategy) -> Result<Parsed> {
+    Ok(Parsed::new(game.parse_guide(input, strategy)?))
 }
 
-impl Choice2 {
-    fn as_directed(&self, other: Choice1) -> Choice2 {
-        match self {
-            Self::Rock => other.win(),
-            Self::Paper => other.draw(),
-            Self::Scissors => other.loose(),
-        }
-    }
+fn solve(game: &Game, parsed: Parsed) -> Result<Answer> {
+    let rounds: Vec<Round> = parsed.take();
+    Ok(score_rounds(game, &rounds).into())
+}
+
+/// What the opponent's moves in the guide allow, whichever way our column
+/// is read.
+pub(crate) fn analyze(game: &Game, input: &str) -> Result<Analysis> {
+    let rounds = game.parse_guide(input, Strategy::Moves)?;
+    Ok(Analysis::new(game, rounds.iter().map(|r| r.theirs)))
 }
-impl TryFrom<char> for Choice2 {
-    type Error = &'static str;
 
-    fn try_from(v: char) -> Result<Self, Self::Error> {
-        match v {
-            'X' => Ok(Choice2::Rock),
-            'Y' => Ok(Choice2::Paper),
-            'Z' => Ok(Choice2::Scissors),
-            _ => Err("Invalid choice"),
+/// Distributions with more totals than this are summarised by quantiles.
+const LISTED_TOTALS: usize = 20;
+
+/// The guide's analysis: the range of totals, what the guide scores under
+/// each strategy against random play, the first `count` rounds explained,
+/// and how random play's total is distributed.
+pub(crate) fn report(input: &str, count: usize) -> Result<String> {
+    let game = Game::rock_paper_scissors();
+    let analysis = analyze(&game, input)?;
+    let mut out = format!(
+        "totals run from {} to {}; random play averages {:.1}",
+        analysis.min,
+        analysis.max,
+        analysis.mean()
+    );
+    for (strategy, name) in [(Strategy::Moves, "moves"), (Strategy::Outcomes, "outcomes")] {
+        let score = score_guide(&game, input, strategy)?;
+        write!(
+            out,
+            "\nread as {name}, the guide scores {score}, more than random play {:.1}% of the time",
+            100.0 * analysis.below(score)
+        )?;
+    }
+    for (n, round) in analysis.rounds.iter().take(count).enumerate() {
+        write!(out, "\n  round {}: {}", n + 1, round.explain(&game))?;
+    }
+    if analysis.distribution().count() <= LISTED_TOTALS {
+        for (score, p) in analysis.distribution() {
+            write!(out, "\n  random total {score}: {:.2}%", 100.0 * p)?;
         }
+    } else {
+        let q = |q| analysis.quantile(q);
+        write!(
+            out,
+            "\n  random totals: p1 {}, p10 {}, p25 {}, median {}, p75 {}, p90 {}, p99 {}",
+            q(0.01),
+            q(0.1),
+            q(0.25),
+            q(0.5),
+            q(0.75),
+            q(0.9),
+            q(0.99)
+        )?;
     }
+    Ok(out)
 }
 
-trait ParseLine {
-    fn parse_as_choice(&self) -> (Choice1, Choice2);
+/// Each line must read as a guide round under both strategies.
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let game = Game::rock_paper_scissors();
+    crate::lint::each_line(input, |line| {
+        game.parse_round(line, Strategy::Moves)?;
+        game.parse_round(line, Strategy::Outcomes)
+    })
 }
 
-impl ParseLine for &str {
-    fn parse_as_choice(&self) -> (Choice1, Choice2) {
-        (
-            self.chars().nth(0).unwrap().try_into().unwrap(),
-            self.chars().nth(2).unwrap().try_into().unwrap(),
-        )
+/// `scale` rounds of the strategy guide.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let game = Game::rock_paper_scissors();
+    let theirs: Vec<&str> = game.their_symbols().collect();
+    let ours: Vec<&str> = game.our_symbols().collect();
+    let mut out = String::new();
+    for _ in 0..scale.max(1) {
+        let theirs = theirs[rng.gen_range(0..theirs.len())];
+        let ours = ours[rng.gen_range(0..ours.len())];
+        writeln!(out, "{theirs} {ours}").unwrap();
     }
+    out
 }
 
 impl PuzzleRun for Part1 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "score following the guide as moves to play"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d2/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        let score = input
-            .lines()
-            .map(|l| l.parse_as_choice())
-            .map(|(c1, c2)| (c1, c2, c1.score(c2)))
-            //.inspect(|v| println!("v: {:?}", v))
-            .map(|(_, _, score)| score)
-            .sum::<u64>();
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
 
-        format!("score: {score}")
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        parse(&self.game, input, Strategy::Moves)
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        solve(&self.game, parsed)
     }
 }
 
 impl PuzzleRun for Part2 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "score following the guide as outcomes to reach"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d2/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        let score = input
-            .lines()
-            .map(|l| l.parse_as_choice())
-            .inspect(|c| println!("line: {:?}", c))
-            .map(|(c1, c2)| (c1, c2, c1.score(c2.as_directed(c1))))
-            .inspect(|v| println!("v: {:?}", v))
-            .map(|(_, _, score)| score)
-            .sum::<u64>();
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        parse(&self.game, input, Strategy::Outcomes)
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        solve(&self.game, parsed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    example_tests!(2: 15, 12);
+
+    #[test]
+    fn test_report() {
+        let report = report(include_str!("example.txt"), 1).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines[0],
+            "totals run from 6 to 24; random play averages 15.0"
+        );
+        assert!(lines[1].starts_with("read as moves, the guide scores 15,"));
+        assert!(lines[2].starts_with("read as outcomes, the guide scores 12,"));
+        assert_eq!(
+            lines[3],
+            "  round 1: against Rock, Paper is a win for 2 + 6 = 8; worst is Scissors, a loss for 3 + 0 = 3"
+        );
+        assert_eq!(lines[4], "  random total 6: 3.70%");
+        assert_eq!(lines.last(), Some(&"  random total 24: 3.70%"));
+        assert!(!report.contains("round 2"));
 
-        format!("score = {score}")
+        let input = "A Y\n".repeat(50);
+        let report = super::report(&input, 0).unwrap();
+        assert!(report.contains("random totals: p1 "), "{report}");
     }
 }
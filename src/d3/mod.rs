@@ -1,129 +1,319 @@
-use crate::PuzzleRun;
-use itertools::Itertools;
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::{bail, Result};
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use tracing::{event, Level};
 
 pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
     vec![Box::new(Part1), Box::new(Part2)]
 }
 
-fn find_duplicate(line: &str) -> Option<char> {
-    if line.len() % 2 != 0 {
-        panic!("invalid str length ({}) for input {}", line.len(), line);
-    } else {
-        let (l, r) = line.split_at(line.len() / 2);
-        l.chars().filter(|c| r.contains(*c)).take(1).next()
+inventory::submit! {
+    crate::Day {
+        number: 3,
+        runs: get_runs,
+        validate,
+        generate,
     }
 }
 
-struct Part2;
+/// A set of items as a bitmask, with bit `p - 1` set for the item of
+/// priority `p`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Items(u64);
 
-impl PuzzleRun for Part2 {
-    fn input_data(&self) -> anyhow::Result<&str> {
-        crate::read_file("src/d3/input.txt")
+impl Items {
+    const NONE: Items = Items(0);
+    const ALL: Items = Items((1 << 52) - 1);
+
+    fn intersect(self, other: Items) -> Items {
+        Items(self.0 & other.0)
+    }
+
+    fn union(self, other: Items) -> Items {
+        Items(self.0 | other.0)
+    }
+
+    fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The items in the set, in priority order.
+    fn iter(self) -> impl Iterator<Item = char> {
+        (1..=52)
+            .filter(move |p| self.0 & (1 << (p - 1)) != 0)
+            .map(item)
+    }
+
+    fn priority_sum(self) -> u64 {
+        (1..=52u64).filter(|p| self.0 & (1 << (p - 1)) != 0).sum()
+    }
+}
+
+/// Items `a` to `z` have priorities 1 to 26, and `A` to `Z` 27 to 52.
+fn priority(c: char) -> Option<u8> {
+    match c {
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 27),
+        _ => None,
+    }
+}
+
+fn item(priority: u8) -> char {
+    match priority {
+        1..=26 => (b'a' + priority - 1) as char,
+        _ => (b'A' + priority - 27) as char,
+    }
+}
+
+/// The two compartments of a rucksack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rucksack {
+    left: Items,
+    right: Items,
+}
+
+impl Rucksack {
+    fn items(&self) -> Items {
+        self.left.union(self.right)
+    }
+
+    /// The items packed in both compartments.
+    fn misplaced(&self) -> Items {
+        self.left.intersect(self.right)
+    }
+}
+
+/// A rucksack is an even number of items, each a letter, split evenly
+/// between its compartments.
+fn parse_rucksack(line: &Line) -> Result<Rucksack, ScanError> {
+    let mut items = [Items::NONE; 2];
+    let half = line.text.len() / 2;
+    for (offset, c) in line.text.char_indices() {
+        let p =
+            priority(c).ok_or_else(|| line.error_at(offset, format!("`{c}` is not an item")))?;
+        let side = &mut items[usize::from(offset >= half)];
+        side.0 |= 1 << (p - 1);
+    }
+    if line.text.is_empty() || !line.text.len().is_multiple_of(2) {
+        return Err(line.error(format!(
+            "expected an even number of items, found {}",
+            line.text.len()
+        )));
+    }
+    let [left, right] = items;
+    Ok(Rucksack { left, right })
+}
+
+/// Every rucksack in `input`, with the line it's on.
+fn parse(input: &str) -> Result<Vec<(usize, Rucksack)>, ScanError> {
+    Scanner::new(input)
+        .lines()
+        .map(|line| Ok((line.number, parse_rucksack(&line)?)))
+        .collect()
+}
+
+/// The priority sum of the items in both compartments of each rucksack.
+fn misplaced_priorities(rucksacks: &[(usize, Rucksack)]) -> Result<u64> {
+    rucksacks
+        .iter()
+        .map(|(line, rucksack)| {
+            let misplaced = rucksack.misplaced();
+            if misplaced.is_empty() {
+                bail!("line {line}: no item in both compartments");
+            }
+            event!(
+                Level::TRACE,
+                "line {line}: {}",
+                misplaced.iter().collect::<String>()
+            );
+            Ok(misplaced.priority_sum())
+        })
+        .sum()
+}
+
+/// The priority sum of the items common to every rucksack in each group
+/// of `size` consecutive rucksacks.
+fn badge_priorities(rucksacks: &[(usize, Rucksack)], size: usize) -> Result<u64> {
+    if size == 0 {
+        bail!("groups must have at least one rucksack");
+    }
+    if !rucksacks.len().is_multiple_of(size) {
+        bail!(
+            "expected groups of {size} rucksacks, found {} lines",
+            rucksacks.len()
+        );
+    }
+    let mut sum = 0;
+    for group in rucksacks.chunks(size) {
+        let common = group
+            .iter()
+            .fold(Items::ALL, |common, (_, r)| common.intersect(r.items()));
+        let (first, last) = (group[0].0, group[size - 1].0);
+        if common.is_empty() {
+            bail!("lines {first}-{last}: no badge common to the group");
+        }
+        event!(
+            Level::TRACE,
+            "lines {first}-{last}: {}",
+            common.iter().collect::<String>()
+        );
+        sum += common.priority_sum();
     }
+    Ok(sum)
+}
 
-    fn run(&self, input: &str) -> String {
-        let mut lines = input.lines();
-        let sum: u64 = std::iter::from_fn(move || lines.next_tuple::<(&str, &str, &str)>())
-            .map(|(a, b, c)| {
-                println!("a: {a}\nb: {b}\nc: {c}");
-                let found = a
-                    .chars()
-                    .find(|a_char| b.contains(*a_char) && c.contains(*a_char))
-                    .unwrap();
-                println!("found: {found}");
-                <u8 as Into<u64>>::into(priority(found as u8))
-            })
-            .sum::<u64>();
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let mut problems = crate::lint::each_line(input, parse_rucksack);
+    let n = input.lines().count();
+    if !n.is_multiple_of(3) {
+        problems.push(ScanError::new(
+            n + 1,
+            1,
+            format!("expected groups of three rucksacks, found {n} lines"),
+        ));
+    }
+    problems
+}
 
-        format!("{}", sum)
+/// `scale` rucksacks, rounded up to whole groups of three.  Every rucksack
+/// has an item in both compartments and every group shares a badge.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut out = String::new();
+    for _ in 0..scale.div_ceil(3).max(1) {
+        let badge = *items.choose(rng).unwrap();
+        for _ in 0..3 {
+            let half = rng.gen_range(4..=16);
+            let mut left: Vec<char> = (0..half).map(|_| *items.choose(rng).unwrap()).collect();
+            let mut right: Vec<char> = (0..half).map(|_| *items.choose(rng).unwrap()).collect();
+            let shared = *items.choose(rng).unwrap();
+            (left[0], left[1], right[0]) = (badge, shared, shared);
+            left.shuffle(rng);
+            right.shuffle(rng);
+            out.extend(left.into_iter().chain(right));
+            out.push('\n');
+        }
     }
+    out
 }
 
 struct Part1;
 
 impl PuzzleRun for Part1 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "priority sum of items found in both compartments"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d3/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        format!("{}", self._run(input))
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
     }
-}
 
-impl Part1 {
-    fn _run(&self, input: &str) -> u64 {
-        input
-            .lines()
-            .map(|l| {
-                let dup = find_duplicate(l).unwrap();
-                let p: u64 = priority(dup as u8).into();
-                p
-            })
-            .sum::<u64>()
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let rucksacks: Vec<(usize, Rucksack)> = parsed.take();
+        Ok(misplaced_priorities(&rucksacks)?.into())
     }
 }
 
-fn priority(in_val: u8) -> u8 {
-    if in_val >= 97 && in_val <= 122 {
-        (in_val - 96).into()
-    } else if in_val >= 65 && in_val <= 90 {
-        (in_val - 64 + 26).into()
-    } else {
-        panic!("invalid character: {in_val}");
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "priority sum of the badges of each group of three"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d3/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let rucksacks: Vec<(usize, Rucksack)> = parsed.take();
+        Ok(badge_priorities(&rucksacks, 3)?.into())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
+
+    example_tests!(3: 157, 70);
+
+    fn rucksack(text: &str) -> Rucksack {
+        parse_rucksack(&Line::new(1, text)).unwrap()
+    }
 
-    use super::{find_duplicate, priority};
+    fn groups(input: &str, size: usize) -> Result<u64> {
+        badge_priorities(&parse(input)?, size)
+    }
 
     #[test]
-    fn test_find_dup() {
-        let s = "vJrwpWtwJgWrhcsFMMfFFhFp";
-        let c = find_duplicate(s);
-        assert!(c.is_some());
-        assert_eq!(c.unwrap(), 'p');
-        assert_eq!(priority(b'p'), 16);
-
-        let s = "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL";
-        let c = find_duplicate(s);
-        assert!(c.is_some());
-        assert_eq!(c.unwrap(), 'L');
-        assert_eq!(priority(b'L'), 38);
-
-        let s = "PmmdzqPrVvPwwTWBwg";
-        let c = find_duplicate(s);
-        assert!(c.is_some());
-        assert_eq!(c.unwrap(), 'P');
-        assert_eq!(priority(b'P'), 42);
-
-        let s = "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn";
-        let c = find_duplicate(s);
-        assert!(c.is_some());
-        assert_eq!(c.unwrap(), 'v');
-        assert_eq!(priority(b'v'), 22);
-
-        let s = "ttgJtRGJQctTZtZT";
-        let c = find_duplicate(s);
-        assert!(c.is_some());
-        assert_eq!(c.unwrap(), 't');
-        assert_eq!(priority(b't'), 20);
-
-        let s = "CrZsJsPPZsGzwwsLwLmpwMDw";
-        let c = find_duplicate(s);
-        assert!(c.is_some());
-        assert_eq!(c.unwrap(), 's');
-        assert_eq!(priority(b's'), 19);
+    fn test_misplaced() {
+        let found: Vec<char> = include_str!("example.txt")
+            .lines()
+            .flat_map(|text| rucksack(text).misplaced().iter())
+            .collect();
+        assert_eq!(found, ['p', 'L', 'P', 'v', 't', 's']);
+        assert_eq!(
+            rucksack("aBcaBc").misplaced().iter().collect::<String>(),
+            "acB"
+        );
+        assert_eq!(rucksack("aBcaBc").misplaced().priority_sum(), 1 + 3 + 28);
+        assert_eq!(priority('p'), Some(16));
+        assert_eq!(priority('L'), Some(38));
+        assert_eq!(priority('-'), None);
+        assert!((1..=52).all(|p| priority(item(p)) == Some(p)));
     }
 
     #[test]
-    fn test_part2() {
-        let lines = "vJrwpWtwJgWrhcsFMMfFFhFp
-            jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-            PmmdzqPrVvPwwTWBwg";
+    fn test_groups() {
+        let input = include_str!("example.txt");
+        // r, then Z
+        assert_eq!(groups(input, 3).unwrap(), 18 + 52);
+        // no item is in all six rucksacks
+        assert_eq!(
+            groups(input, 6).unwrap_err().to_string(),
+            "lines 1-6: no badge common to the group"
+        );
+        // every item of a lone rucksack is its own badge
+        assert_eq!(groups("abba\n", 1).unwrap(), 3);
+        assert_eq!(
+            groups("abab\nbaba\nabab\n", 2).unwrap_err().to_string(),
+            "expected groups of 2 rucksacks, found 3 lines"
+        );
+    }
 
-        assert_eq!(super::Part1::_run(&super::Part1, lines), 18);
+    #[test]
+    fn test_errors() {
+        let e = parse_rucksack(&Line::new(2, "ab1c")).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 3));
+        assert!(parse_rucksack(&Line::new(1, "abc")).is_err());
+        assert!(parse_rucksack(&Line::new(1, "")).is_err());
+        assert!(misplaced_priorities(&parse("abcd\n").unwrap()).is_err());
     }
 }
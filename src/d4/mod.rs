@@ -1,86 +1,363 @@
-use crate::PuzzleRun;
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError, Token};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use rand::Rng as _;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Write;
 use std::str::FromStr;
+use tracing::{enabled, event, Level};
 
 pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
     vec![Box::new(Part1), Box::new(Part2)]
 }
 
-fn split(line: &str) -> Result<(&str, &str), &'static str> {
-    line.split_once(',').ok_or("bad line: {line}")
+inventory::submit! {
+    crate::Day {
+        number: 4,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+/// The sections from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Inclusive<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Ord + Copy> Inclusive<T> {
+    /// The range from `start` to `end`, unless it runs backwards.
+    fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// Whether every section of `other` is in this range.
+    fn contains(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The sections in both ranges, if any.
+    fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+impl<T: Ord + Copy + Into<i128>> Inclusive<T> {
+    /// How many sections the range covers.
+    fn len(&self) -> u128 {
+        (self.end.into() - self.start.into()) as u128 + 1
+    }
+
+    fn intersection_len(&self, other: &Self) -> u128 {
+        self.intersection(other).map_or(0, |r| r.len())
+    }
+
+    /// The single range covering both, if they overlap or meet end to end.
+    fn union(&self, other: &Self) -> Option<Self> {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        (second.start.into() - first.end.into() <= 1).then(|| Self {
+            start: first.start,
+            end: first.end.max(second.end),
+        })
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Inclusive<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+pub(crate) type Sections = Inclusive<u64>;
+
+type Pair = (Sections, Sections);
+
+/// One elf's assignment: the line it's on, and whether it's the first (0)
+/// or second (1) of the pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Elf {
+    pub(crate) line: usize,
+    pub(crate) side: usize,
+}
+
+impl std::fmt::Display for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let side = if self.side == 0 { "first" } else { "second" };
+        write!(f, "the {side} elf on line {}", self.line)
+    }
 }
 
-fn to_range(desc: &str) -> Result<(u8, u8), &'static str> {
-    let (a_str, b_str) = desc.split_once('-').ok_or("bad desc: {desc}")?;
-    let a = u8::from_str(a_str).map_err(|_| "bad str: {a_str}")?;
-    let b = u8::from_str(b_str).map_err(|_| "bad str: {b_str}")?;
-    Ok((a, b))
+/// Every elf's assignment in `input`, in order.
+pub(crate) fn assignments(input: &str) -> Result<Vec<(Elf, Sections)>, ScanError> {
+    let mut elves = vec![];
+    for line in Scanner::new(input).lines() {
+        let (a, b) = parse_line(&line)?;
+        elves.push((
+            Elf {
+                line: line.number,
+                side: 0,
+            },
+            a,
+        ));
+        elves.push((
+            Elf {
+                line: line.number,
+                side: 1,
+            },
+            b,
+        ));
+    }
+    Ok(elves)
+}
+
+/// Sweep over the assignments by start, calling `found` with each elf and
+/// the earlier-starting elves whose ranges are still open at its start,
+/// which are exactly the earlier elves it overlaps.
+fn sweep(elves: &[(Elf, Sections)], mut found: impl FnMut(Elf, &BinaryHeap<Reverse<(u64, Elf)>>)) {
+    let mut by_start: Vec<&(Elf, Sections)> = elves.iter().collect();
+    by_start.sort_by_key(|(_, r)| r.start);
+
+    let mut open: BinaryHeap<Reverse<(u64, Elf)>> = BinaryHeap::new();
+    for &(elf, range) in by_start {
+        while open
+            .peek()
+            .is_some_and(|Reverse((end, _))| *end < range.start)
+        {
+            open.pop();
+        }
+        found(elf, &open);
+        open.push(Reverse((range.end, elf)));
+    }
 }
-fn included_in(a: (u8, u8), b: (u8, u8)) -> bool {
-    (a.0 >= b.0 && a.1 <= b.1) || (b.0 >= a.0 && b.1 <= a.1)
+
+/// Every pair of elves whose assignments overlap, each pair ordered and the
+/// list sorted.  The cost is in the sort and the pairs reported.
+pub(crate) fn overlapping_pairs(elves: &[(Elf, Sections)]) -> Vec<(Elf, Elf)> {
+    let mut pairs = vec![];
+    sweep(elves, |elf, open| {
+        pairs.extend(
+            open.iter()
+                .map(|Reverse((_, other))| (elf.min(*other), elf.max(*other))),
+        );
+    });
+    pairs.sort();
+    pairs
 }
 
-fn overlaps(a: (u8, u8), b: (u8, u8)) -> bool {
-    (a.0 <= b.1 && a.1 >= b.0) || (b.0 <= a.1 && b.1 >= a.0)
+/// How many pairs of elves overlap, without listing them.
+pub(crate) fn count_overlapping_pairs(elves: &[(Elf, Sections)]) -> usize {
+    let mut count = 0;
+    sweep(elves, |_, open| count += open.len());
+    count
 }
 
-fn check<'a, L, F>(lines: L, predicate: F) -> u64
+/// The elves whose assignment overlaps at least one other elf's, in order.
+/// In start order, an elf overlaps someone exactly when an earlier range
+/// reaches it or the next range starts within it.
+pub(crate) fn overlapping_elves(elves: &[(Elf, Sections)]) -> Vec<Elf> {
+    let mut by_start: Vec<&(Elf, Sections)> = elves.iter().collect();
+    by_start.sort_by_key(|(_, r)| (r.start, r.end));
+
+    let mut found = vec![];
+    let mut reach: Option<u64> = None;
+    for (i, (elf, range)) in by_start.iter().enumerate() {
+        let before = reach.is_some_and(|end| end >= range.start);
+        let after = by_start
+            .get(i + 1)
+            .is_some_and(|(_, next)| next.start <= range.end);
+        if before || after {
+            found.push(*elf);
+        }
+        reach = Some(reach.map_or(range.end, |end| end.max(range.end)));
+    }
+    found.sort();
+    found
+}
+
+/// How many sections at least one elf is assigned.
+pub(crate) fn covered(elves: &[(Elf, Sections)]) -> u128 {
+    let mut ranges: Vec<Sections> = elves.iter().map(|(_, r)| *r).collect();
+    ranges.sort_by_key(|r| r.start);
+    let mut total = 0;
+    let mut run: Option<Sections> = None;
+    for range in ranges {
+        run = match run.and_then(|run| run.union(&range)) {
+            Some(merged) => Some(merged),
+            None => {
+                total += run.map_or(0, |r| r.len());
+                Some(range)
+            }
+        };
+    }
+    total + run.map_or(0, |r| r.len())
+}
+
+fn parse_range<T>(token: &Token) -> Result<Inclusive<T>, ScanError>
 where
-    L: Iterator<Item = &'a str>,
-    F: Fn((u8, u8), (u8, u8)) -> bool,
+    T: FromStr + Ord + Copy + std::fmt::Display,
+    T::Err: std::fmt::Display,
 {
-    let c = lines
-        .map(|l| split(l).unwrap())
-        .map(|(a, b)| (to_range(a).unwrap(), to_range(b).unwrap()))
-        .filter(|(range_a, range_b)| predicate(*range_a, *range_b))
-        .count();
-    c as u64
+    let (a, b) = token.pair("-")?;
+    Inclusive::new(a, b).ok_or_else(|| token.error(format!("range {a}-{b} runs backwards")))
 }
 
-fn _part1_old<'a, L>(lines: L) -> Result<u64, &'static str>
+fn parse_line(line: &Line) -> Result<Pair, ScanError> {
+    match line.split(",").as_slice() {
+        [a, b] => Ok((parse_range(a)?, parse_range(b)?)),
+        _ => Err(line.error("expected two ranges separated by `,`")),
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    crate::lint::each_line(input, parse_line)
+}
+
+/// `scale` pairs of section assignments.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..scale.max(1) {
+        let [a, b, c, d] = [(); 4].map(|_| rng.gen_range(1..=99));
+        writeln!(out, "{}-{},{}-{}", a.min(b), a.max(b), c.min(d), c.max(d)).unwrap();
+    }
+    out
+}
+
+/// How many pairs of elves, read from consecutive assignments, satisfy
+/// `predicate`.
+fn count_pairs<F>(elves: &[(Elf, Sections)], predicate: F) -> u64
 where
-    L: Iterator<Item = &'a str>,
+    F: Fn(&Sections, &Sections) -> bool,
 {
-    let c = lines
-        .map(|l| split(l).unwrap())
-        .map(|(a, b)| (to_range(a).unwrap(), to_range(b).unwrap()))
-        .filter(|(range_a, range_b)| included_in(*range_a, *range_b))
-        .count();
-    Ok(c as u64)
+    elves
+        .chunks(2)
+        .filter(|pair| predicate(&pair[0].1, &pair[1].1))
+        .count() as u64
+}
+
+/// How many pairs of elves and single elves overlap, given the elves in
+/// `overlapping`, and how many sections are assigned twice within a pair
+/// and at all.
+fn summary(elves: &[(Elf, Sections)], overlapping: &[Elf]) -> String {
+    let doubled: u128 = elves
+        .chunks(2)
+        .map(|pair| pair[0].1.intersection_len(&pair[1].1))
+        .sum();
+    format!(
+        "{} pairs of elves overlap; {} of {} elves overlap someone; \
+         {doubled} sections are assigned twice within a pair, and {} are assigned at all",
+        count_overlapping_pairs(elves),
+        overlapping.len(),
+        elves.len(),
+        covered(elves)
+    )
+}
+
+/// The overlap summary, then the first `count` pairs of elves that overlap
+/// and the first `count` elves that overlap someone.
+pub(crate) fn report(input: &str, count: usize) -> anyhow::Result<String> {
+    let elves = assignments(input)?;
+    let overlapping = overlapping_elves(&elves);
+    let mut out = summary(&elves, &overlapping);
+    let pairs = overlapping_pairs(&elves);
+    for (a, b) in pairs.iter().take(count) {
+        write!(out, "\n  {a} and {b} overlap")?;
+    }
+    if pairs.len() > count {
+        write!(out, "\n  and {} more pairs", pairs.len() - count)?;
+    }
+    for elf in overlapping.iter().take(count) {
+        write!(out, "\n  {elf} overlaps someone")?;
+    }
+    if overlapping.len() > count {
+        write!(out, "\n  and {} more elves", overlapping.len() - count)?;
+    }
+    Ok(out)
 }
 
 struct Part1;
 
 impl crate::PuzzleRun for Part1 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "pairs where one assignment contains the other"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d4/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        format!("{}", Part1::_run(input))
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
     }
-}
 
-impl Part1 {
-    fn _run(input: &str) -> u64 {
-        check(input.lines(), included_in)
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(assignments(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let elves: Vec<(Elf, Sections)> = parsed.take();
+        Ok(count_pairs(&elves, |a, b| a.contains(b) || b.contains(a)).into())
     }
 }
 
 impl crate::PuzzleRun for Part2 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "pairs with overlapping assignments"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d4/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        format!("{}", Part2::_run(input))
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(assignments(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let elves: Vec<(Elf, Sections)> = parsed.take();
+        Ok(Part2::count(&elves).into())
     }
 }
 
 struct Part2;
 
 impl Part2 {
-    fn _run(input: &str) -> u64 {
-        check(input.lines(), overlaps)
+    fn count(elves: &[(Elf, Sections)]) -> u64 {
+        if enabled!(Level::DEBUG) {
+            let overlapping = overlapping_elves(elves);
+            event!(Level::DEBUG, "{}", summary(elves, &overlapping));
+            if enabled!(Level::TRACE) {
+                for elf in overlapping {
+                    event!(Level::TRACE, "{elf} overlaps someone");
+                }
+                for (a, b) in overlapping_pairs(elves) {
+                    event!(Level::TRACE, "{a} and {b} overlap");
+                }
+            }
+        }
+        count_pairs(elves, Sections::overlaps)
     }
 }
 
@@ -88,21 +365,104 @@ impl Part2 {
 mod test {
     use super::*;
 
+    example_tests!(4: 2, 4);
+
+    fn range(start: u64, end: u64) -> Sections {
+        Inclusive::new(start, end).unwrap()
+    }
+
     #[test]
-    fn test_included() {
-        assert!(included_in((2, 8), (3, 7)));
-        assert!(included_in((3, 7), (2, 8)));
+    fn test_contains() {
+        assert!(range(2, 8).contains(&range(3, 7)));
+        assert!(!range(3, 7).contains(&range(2, 8)));
+        assert!(range(6, 6).contains(&range(6, 6)));
 
-        assert!(!included_in((5, 7), (7, 9)));
+        assert!(!range(5, 7).contains(&range(7, 9)));
+        assert_eq!(Inclusive::new(3, 2), None);
     }
 
     #[test]
     fn test_overlaps() {
-        assert!(overlaps((2, 8), (3, 7)));
-        assert!(overlaps((3, 7), (2, 8)));
-        assert!(overlaps((5, 7), (7, 9)));
-        assert!(overlaps((7, 9), (5, 7)));
+        assert!(range(2, 8).overlaps(&range(3, 7)));
+        assert!(range(3, 7).overlaps(&range(2, 8)));
+        assert!(range(5, 7).overlaps(&range(7, 9)));
+        assert!(range(7, 9).overlaps(&range(5, 7)));
+
+        assert!(!range(2, 3).overlaps(&range(4, 5)));
+    }
+
+    #[test]
+    fn test_set_ops() {
+        assert_eq!(range(2, 8).intersection(&range(5, 12)), Some(range(5, 8)));
+        assert_eq!(range(2, 3).intersection(&range(4, 5)), None);
+        assert_eq!(range(2, 8).intersection_len(&range(5, 12)), 4);
+        assert_eq!(range(2, 3).intersection_len(&range(4, 5)), 0);
+        assert_eq!(range(0, u64::MAX).len(), u128::from(u64::MAX) + 1);
+
+        assert_eq!(range(4, 5).union(&range(2, 3)), Some(range(2, 5)));
+        assert_eq!(range(2, 9).union(&range(4, 5)), Some(range(2, 9)));
+        assert_eq!(range(2, 3).union(&range(5, 6)), None);
+        assert_eq!(
+            Inclusive::new(-3i32, -1)
+                .unwrap()
+                .union(&Inclusive::new(0, 2).unwrap()),
+            Inclusive::new(-3, 2)
+        );
+    }
+
+    #[test]
+    fn test_large_sections() {
+        let (a, b) = parse_line(&Line::new(1, "200-1000,999-4000000000")).unwrap();
+        assert!(a.overlaps(&b));
+        assert!(parse_line(&Line::new(1, "9-3,1-2")).is_err());
+    }
+
+    #[test]
+    fn test_sweep() {
+        let elves = assignments(include_str!("example.txt")).unwrap();
+        let pairs = overlapping_pairs(&elves);
+        let elf = |line, side| Elf { line, side };
+
+        // every pair the sweep finds overlaps, and no other pair does
+        let mut expected = vec![];
+        for (i, (a, ra)) in elves.iter().enumerate() {
+            for (b, rb) in elves[i + 1..].iter() {
+                if ra.overlaps(rb) {
+                    expected.push((*a.min(b), *a.max(b)));
+                }
+            }
+        }
+        expected.sort();
+        assert_eq!(pairs, expected);
+        assert_eq!(count_overlapping_pairs(&elves), pairs.len());
+        assert!(pairs.contains(&(elf(3, 0), elf(3, 1))));
+        assert!(pairs.contains(&(elf(1, 0), elf(6, 1))));
+
+        let mut anyone: Vec<Elf> = pairs.iter().flat_map(|&(a, b)| [a, b]).collect();
+        anyone.sort();
+        anyone.dedup();
+        assert_eq!(overlapping_elves(&elves), anyone);
+
+        assert_eq!(covered(&elves), 8);
+        assert_eq!(elf(6, 1).to_string(), "the second elf on line 6");
+
+        let report = report(include_str!("example.txt"), 1).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with(&format!("{} pairs of elves overlap", pairs.len())));
+        assert_eq!(
+            &lines[1..],
+            [
+                &format!("  {} and {} overlap", pairs[0].0, pairs[0].1),
+                &format!("  and {} more pairs", pairs.len() - 1),
+                &format!("  {} overlaps someone", anyone[0]),
+                &format!("  and {} more elves", anyone.len() - 1),
+            ]
+        );
 
-        assert!(!overlaps((2, 3), (4, 5)));
+        let apart = assignments("1-2,4-5\n7-8,10-11\n").unwrap();
+        assert!(overlapping_pairs(&apart).is_empty());
+        assert_eq!(count_overlapping_pairs(&apart), 0);
+        assert!(overlapping_elves(&apart).is_empty());
+        assert_eq!(covered(&apart), 8);
     }
 }
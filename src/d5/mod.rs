@@ -1,108 +1,414 @@
-use anyhow::{Context, Result};
-use regex::Regex;
-use std::str::FromStr;
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError, Token};
+use crate::{Answer, Parsed, Scanner};
+use anyhow::{bail, Context, Result};
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::fmt::Write;
+use tracing::{event, Level};
 
 pub fn get_runs() -> Vec<Box<dyn crate::PuzzleRun>> {
-    vec![Box::new(Part2)]
+    vec![Box::new(Part1), Box::new(Part2)]
 }
 
-fn print_stacks(stacks: &Vec<Vec<char>>) {
+inventory::submit! {
+    crate::Day {
+        number: 5,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+fn print_stacks(stacks: &[Vec<char>]) {
     for (n, stack) in stacks.iter().enumerate() {
-        println!("stack {}: {:?}", n + 1, stack);
+        event!(Level::TRACE, "stack {}: {:?}", n + 1, stack);
     }
 }
 
-fn runit<'a, I, F>(lines: I, stacks: &mut Vec<Vec<char>>, part_fn: F)
-where
-    I: Iterator<Item = &'a str>,
-    F: Fn(u8, &mut Vec<char>, &mut Vec<char>),
-{
-    let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-    for line in lines {
-        print_stacks(stacks);
-        println!("instr: {line}");
-        let Some(captures) = re.captures(line) else {
-            continue;
-        };
-        let (count, from, to) = (
-            u8::from_str(captures.get(1).unwrap().as_str()).unwrap(),
-            u8::from_str(captures.get(2).unwrap().as_str()).unwrap(),
-            u8::from_str(captures.get(3).unwrap().as_str()).unwrap(),
-        );
+/// The stacks are numbered from 1 along the row under the drawing.  Each
+/// label's columns are where that stack's crates sit.
+fn parse_labels(line: &Line) -> Result<Vec<std::ops::Range<usize>>, ScanError> {
+    let labels: Vec<Token> = line.tokens().collect();
+    for (i, label) in labels.iter().enumerate() {
+        if label.text != (i + 1).to_string() {
+            return Err(label.error(format!("expected stack label {}", i + 1)));
+        }
+    }
+    if labels.is_empty() {
+        return Err(line.error("expected stack labels"));
+    }
+    Ok(labels
+        .iter()
+        .map(|label| label.column - 1..label.column - 1 + label.text.len())
+        .collect())
+}
+
+/// One row of the drawing: `[X]` crates, each centred under a stack label,
+/// and spaces.  Rows may stop short after their last crate.
+fn parse_row(
+    line: &Line,
+    labels: &[std::ops::Range<usize>],
+) -> Result<Vec<Option<char>>, ScanError> {
+    let mut row = vec![None; labels.len()];
+    let bytes = line.text.as_bytes();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        match bytes[offset..] {
+            [b' ', ..] => offset += 1,
+            [b'[', c, b']', ..] if c.is_ascii_uppercase() => {
+                let stack = labels
+                    .iter()
+                    .position(|l| l.contains(&(offset + 1)))
+                    .ok_or_else(|| line.error_at(offset, "crate isn't under a stack label"))?;
+                row[stack] = Some(c as char);
+                offset += 3;
+            }
+            _ => return Err(line.error_at(offset, "expected a crate like `[A]`")),
+        }
+    }
+    Ok(row)
+}
 
-        assert!(from != to);
-        assert!(from > 0 && (from as usize) <= stacks.len());
-        assert!(to > 0 && (to as usize) <= stacks.len());
+/// The drawing up to and including the blank line after the labels, as
+/// stacks listed bottom to top.  The labels give the number of stacks and
+/// the rows above them the height.
+fn parse_drawing(scanner: &mut Scanner) -> Result<Vec<Vec<char>>, ScanError> {
+    let mut rows = vec![];
+    let labels = loop {
+        let line = scanner.next_line().ok_or_else(|| {
+            ScanError::new(
+                rows.len() + 1,
+                1,
+                "expected stack labels, found end of input",
+            )
+        })?;
+        let text = line.text.trim_start();
+        if text.starts_with(|c: char| c.is_ascii_digit()) {
+            break parse_labels(&line)?;
+        }
+        if text.starts_with("move") {
+            return Err(line.error("expected stack labels before the moves"));
+        }
+        rows.push(line);
+    };
+    let line = scanner.next_line().ok_or_else(|| {
+        ScanError::new(
+            rows.len() + 2,
+            1,
+            "expected a blank line, found end of input",
+        )
+    })?;
+    parse_separator(&line)?;
 
-        unsafe {
-            let from_stack = &mut *(stacks.get_unchecked_mut(from as usize - 1) as *mut _);
-            let to_stack = &mut *(stacks.get_unchecked_mut(to as usize - 1) as *mut _);
-            part_fn(count, from_stack, to_stack);
+    let mut stacks: Vec<Vec<char>> = vec![vec![]; labels.len()];
+    for (height, line) in rows.iter().rev().enumerate() {
+        for (i, c) in parse_row(line, &labels)?.into_iter().enumerate() {
+            let Some(c) = c else { continue };
+            if stacks[i].len() < height {
+                return Err(line.error_at(
+                    labels[i].start.saturating_sub(1),
+                    format!("crate floats above a gap in stack {}", i + 1),
+                ));
+            }
+            stacks[i].push(c);
         }
     }
+    Ok(stacks)
 }
 
-fn _part1(count: u8, from_stack: &mut Vec<char>, to_stack: &mut Vec<char>) -> Result<()> {
-    if count > 0 {
-        let obj = from_stack.pop().context("stack {from} is empty")?;
-        to_stack.push(obj);
-        _part1(count - 1, from_stack, to_stack)?;
+fn parse_separator(line: &Line) -> Result<(), ScanError> {
+    match line.text.trim() {
+        "" => Ok(()),
+        _ => Err(line.error("expected a blank line after the stack labels")),
     }
-    Ok(())
 }
 
-fn part2(count: u8, from_stack: &mut Vec<char>, to_stack: &mut Vec<char>) {
-    if count > 0 {
-        let start = from_stack.len() - count as usize;
-        let items = from_stack.drain(start..);
-        to_stack.extend(items);
+struct Move {
+    count: usize,
+    from: usize,
+    to: usize,
+    /// The line the move is on, for errors in making it.
+    line: usize,
+}
+
+/// A `move N from A to B` line, checked against the number of stacks.
+fn parse_move(line: &Line, stacks: usize) -> Result<Move, ScanError> {
+    let tokens: Vec<Token> = line.tokens().collect();
+    let [verb, count, from_word, from, to_word, to] = tokens.as_slice() else {
+        return Err(line.error("expected `move N from A to B`"));
+    };
+    for (token, word) in [(verb, "move"), (from_word, "from"), (to_word, "to")] {
+        if token.text != word {
+            return Err(token.error(format!("expected `{word}`")));
+        }
+    }
+    let stack = |token: &Token| -> Result<usize, ScanError> {
+        match token.parse()? {
+            n @ 1.. if n <= stacks => Ok(n),
+            n => Err(token.error(format!("no stack {n}"))),
+        }
+    };
+    let m = Move {
+        count: count.parse()?,
+        from: stack(from)?,
+        to: stack(to)?,
+        line: line.number,
+    };
+    if m.from == m.to {
+        return Err(to.error(format!("move from stack {} to itself", m.from)));
+    }
+    Ok(m)
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let mut scanner = Scanner::new(input);
+    let stacks = match parse_drawing(&mut scanner) {
+        Ok(stacks) => stacks.len(),
+        Err(e) => return vec![e],
+    };
+    scanner
+        .lines()
+        .filter_map(|line| parse_move(&line, stacks).err())
+        .collect()
+}
+
+/// Generated drawings are shaped like the real input: nine stacks, at most
+/// eight crates high.
+const STACKS: usize = 9;
+const HEIGHT: usize = 8;
+
+/// A random drawing followed by `scale` moves, each taking crates from a
+/// stack that has enough of them.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let mut stacks: Vec<Vec<char>> = (0..STACKS)
+        .map(|_| {
+            let height = rng.gen_range(0..=HEIGHT);
+            (0..height).map(|_| rng.gen_range('A'..='Z')).collect()
+        })
+        .collect();
+    if stacks.iter().all(|s| s.is_empty()) {
+        stacks[0].push('A');
+    }
+
+    let mut out = String::new();
+    for level in (0..HEIGHT).rev() {
+        let row: Vec<String> = stacks
+            .iter()
+            .map(|s| match s.get(level) {
+                Some(c) => format!("[{c}]"),
+                None => "   ".to_string(),
+            })
+            .collect();
+        writeln!(out, "{}", row.join(" ")).unwrap();
+    }
+    let labels: Vec<String> = (1..=STACKS).map(|i| format!(" {i} ")).collect();
+    writeln!(out, "{}\n", labels.join(" ")).unwrap();
+
+    for _ in 0..scale.max(1) {
+        let full: Vec<usize> = (0..STACKS).filter(|&i| !stacks[i].is_empty()).collect();
+        let from = *full.choose(rng).unwrap();
+        let to = (from + rng.gen_range(1..STACKS)) % STACKS;
+        let count = rng.gen_range(1..=stacks[from].len());
+        let keep = stacks[from].len() - count;
+        let moved = stacks[from].split_off(keep);
+        stacks[to].extend(moved);
+        writeln!(out, "move {count} from {} to {}", from + 1, to + 1).unwrap();
+    }
+    out
+}
+
+/// How a crane moves several crates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Crane {
+    /// One crate at a time, so they land in reverse order.
+    CrateMover9000,
+    /// All at once, so they keep their order.
+    CrateMover9001,
+}
+
+impl Crane {
+    fn apply(self, stacks: &mut [Vec<char>], m: &Move) -> Result<()> {
+        let from = &mut stacks[m.from - 1];
+        let Some(keep) = from.len().checked_sub(m.count) else {
+            bail!(
+                "can't move {} crates from stack {}, which holds {}",
+                m.count,
+                m.from,
+                from.len()
+            );
+        };
+        let mut moved = from.split_off(keep);
+        if self == Crane::CrateMover9000 {
+            moved.reverse();
+        }
+        stacks[m.to - 1].extend(moved);
+        Ok(())
+    }
+}
+
+/// The drawing's stacks and the moves to make on them.
+struct Plan {
+    stacks: Vec<Vec<char>>,
+    moves: Vec<Move>,
+}
+
+fn parse(input: &str) -> Result<Plan> {
+    let mut scanner = Scanner::new(input);
+    let stacks = parse_drawing(&mut scanner)?;
+    let moves = scanner
+        .lines()
+        .map(|line| parse_move(&line, stacks.len()))
+        .collect::<Result<_, _>>()?;
+    Ok(Plan { stacks, moves })
+}
+
+/// The crates on top of each stack after `crane` makes every move.  An
+/// empty stack shows as a space, so each stack keeps its place.
+fn rearrange(plan: Plan, crane: Crane) -> Result<String> {
+    let Plan { mut stacks, moves } = plan;
+    for m in moves.iter() {
+        print_stacks(&stacks);
+        event!(
+            Level::TRACE,
+            "instr: move {} from {} to {}",
+            m.count,
+            m.from,
+            m.to
+        );
+        crane
+            .apply(&mut stacks, m)
+            .with_context(|| format!("line {}", m.line))?;
+    }
+
+    event!(Level::DEBUG, "final stacks");
+    for (n, stack) in stacks.iter().enumerate() {
+        event!(Level::DEBUG, "stack {}: {:?}", n + 1, stack);
+    }
+    Ok(stacks.iter().map(|s| s.last().unwrap_or(&' ')).collect())
+}
+
+struct Part1;
+
+impl crate::PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "top crates when moving one crate at a time"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d5/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        rearrange(parsed.take(), Crane::CrateMover9000).map(Answer::from)
     }
 }
 
 struct Part2;
 
 impl crate::PuzzleRun for Part2 {
-    fn input_data(&self) -> anyhow::Result<&str> {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "top crates when moving several crates at once"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
         crate::read_file("src/d5/input.txt")
     }
 
-    fn run(&self, input: &str) -> String {
-        let mut lines = input.lines();
-        let mut stacks: Vec<Vec<char>> = vec![
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-        ];
-
-        for _ in 0..8 {
-            let line = lines.next().unwrap();
-            for i in 0..9 {
-                let c = line.chars().nth(1 + i * 4).unwrap();
-                if c.is_ascii_alphabetic() {
-                    stacks[i].push(c);
-                }
-            }
-        }
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
 
-        for stack in &mut stacks {
-            stack.reverse();
-        }
-        let _ = lines.next();
-        let _ = lines.next();
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        rearrange(parsed.take(), Crane::CrateMover9001).map(Answer::from)
+    }
+}
 
-        runit(lines, &mut stacks, part2);
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        println!("FINAL ...");
-        print_stacks(&stacks);
-        for (n, stack) in stacks.iter().enumerate() {
-            println!("stack {n}: {}", stack[stack.len() - 1]);
-        }
-        "(nada)".to_string()
+    example_tests!(5: "CMZ", "MCD");
+
+    fn drawing(input: &str) -> Result<Vec<Vec<char>>, ScanError> {
+        parse_drawing(&mut Scanner::new(input))
+    }
+
+    #[test]
+    fn test_drawing() {
+        // ragged rows, and a stack with nothing in it
+        let stacks = drawing("[A]\n[B]     [C]\n 1   2   3\n\n").unwrap();
+        assert_eq!(stacks, [vec!['B', 'A'], vec![], vec!['C']]);
+        // labels alone make empty stacks
+        assert_eq!(drawing(" 1   2 \n\n").unwrap(), [vec![], vec![]]);
+
+        let e = drawing("    [A]\n        \n 1   2\n\n").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 5));
+        let e = drawing("        [A]\n 1   2\n\n").unwrap_err();
+        assert_eq!(e.msg, "crate isn't under a stack label");
+        let e = drawing("[A] (B)\n 1   2\n\n").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 5));
+        let e = drawing("[A]\n 1   3\n\n").unwrap_err();
+        assert_eq!(e.msg, "expected stack label 2");
+        assert!(drawing("[A]\nmove 1 from 1 to 2\n").is_err());
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut stacks = vec![vec!['A', 'B', 'C'], vec![]];
+        let m = Move {
+            count: 2,
+            from: 1,
+            to: 2,
+            line: 1,
+        };
+        Crane::CrateMover9000.apply(&mut stacks, &m).unwrap();
+        assert_eq!(stacks, [vec!['A'], vec!['C', 'B']]);
+        assert!(Crane::CrateMover9001.apply(&mut stacks, &m).is_err());
+        assert_eq!(stacks, [vec!['A'], vec!['C', 'B']]);
+
+        assert!(parse_move(&Line::new(1, "move 1 from 2 to 2"), 2).is_err());
+        let e = rearrange(
+            parse("[A]\n 1   2\n\nmove 1 from 2 to 1\n").unwrap(),
+            Crane::CrateMover9000,
+        )
+        .unwrap_err();
+        assert_eq!(
+            format!("{e:#}"),
+            "line 4: can't move 1 crates from stack 2, which holds 0"
+        );
+    }
+
+    #[test]
+    fn test_empty_stacks() {
+        let input = "[A]\n[B]\n 1   2   3\n\nmove 2 from 1 to 3\n";
+        assert_eq!(
+            rearrange(parse(input).unwrap(), Crane::CrateMover9000).unwrap(),
+            "  B"
+        );
+        assert_eq!(
+            rearrange(parse(input).unwrap(), Crane::CrateMover9001).unwrap(),
+            "  A"
+        );
     }
 }
@@ -0,0 +1,168 @@
+use crate::generate::Rng;
+use crate::scanner::ScanError;
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::{anyhow, Result};
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 6,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let mut scanner = Scanner::new(input);
+    let Some(line) = scanner.next_line() else {
+        return vec![ScanError::new(
+            1,
+            1,
+            "expected a signal, found end of input",
+        )];
+    };
+    let mut problems: Vec<ScanError> = line
+        .text
+        .char_indices()
+        .filter(|(_, c)| !c.is_ascii_lowercase())
+        .map(|(offset, c)| line.error_at(offset, format!("unexpected `{c}`")))
+        .collect();
+    if line.text.len() < 14 {
+        problems.push(line.error("signal is shorter than a start-of-message marker"));
+    }
+    problems.extend(crate::lint::trailing(&mut scanner));
+    problems
+}
+
+/// A signal of `scale` characters whose only start-of-message marker is at
+/// the very end, so the search has to cover all of it.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    // thirteen letters can't make fourteen different ones
+    let mut signal: String = (0..scale.max(14) - 14)
+        .map(|_| rng.gen_range('a'..='m'))
+        .collect();
+    let mut marker: Vec<char> = ('a'..='z').collect();
+    marker.shuffle(rng);
+    signal.extend(&marker[..14]);
+    signal.push('\n');
+    signal
+}
+
+fn found_marker(buf: &[u8]) -> bool {
+    for i in 0..buf.len() - 1 {
+        for j in i + 1..buf.len() {
+            if buf[i] == buf[j] {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn find_packet_marker(data: &[u8]) -> Result<usize> {
+    find_marker(data, 4)
+}
+
+fn find_message_marker(data: &[u8]) -> Result<usize> {
+    find_marker(data, 14)
+}
+
+fn find_marker(data: &[u8], size: usize) -> Result<usize> {
+    if data.len() < size {
+        return Err(anyhow!("input is shorter than the marker size ({size})"));
+    }
+    let mut buf: Vec<u8> = data[0..size].to_owned();
+    let mut pos = size;
+    loop {
+        if found_marker(&buf) {
+            return Ok(pos);
+        }
+        if pos == data.len() {
+            return Err(anyhow!("no marker of size {size} found"));
+        }
+        buf[pos % size] = data[pos];
+        pos += 1;
+    }
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "position of the first start-of-packet marker"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d6/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(input.trim().as_bytes().to_vec()))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let pos = find_packet_marker(&parsed.take::<Vec<u8>>())?;
+        Ok(pos.into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "position of the first start-of-message marker"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d6/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(input.trim().as_bytes().to_vec()))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let pos = find_message_marker(&parsed.take::<Vec<u8>>())?;
+        Ok(pos.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    example_tests!(6: 7, 19);
+
+    #[test]
+    fn test_markers() {
+        let data = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(find_packet_marker(data).unwrap(), 7);
+        assert_eq!(find_message_marker(data).unwrap(), 19);
+
+        let data = b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+        assert_eq!(find_packet_marker(data).unwrap(), 10);
+        assert_eq!(find_message_marker(data).unwrap(), 29);
+    }
+}
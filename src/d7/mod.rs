@@ -0,0 +1,399 @@
+use crate::generate::Rng;
+use crate::scanner::{Line, ScanError, Token};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::{anyhow, Context, Result};
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::cell::RefCell;
+use std::collections::{hash_map::Entry, HashMap};
+use std::fmt::Write;
+use std::rc::{Rc, Weak};
+use tracing::{event, Level};
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 7,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+struct File {
+    name: String,
+    size: u64,
+}
+
+impl File {
+    fn new(name: &str, size: u64 /*, dir: Rc<RefCell<Dir>> */) -> Self {
+        Self {
+            name: name.to_owned(),
+            size,
+        }
+    }
+
+    fn dump(&self, level: usize) {
+        event!(
+            Level::TRACE,
+            "{}file `{}' with size {}",
+            "    ".repeat(level),
+            self.name,
+            self.size
+        );
+    }
+}
+struct Dir {
+    name: String,
+    dirs: HashMap<String, Rc<RefCell<Dir>>>,
+    files: Vec<File>,
+    parent: Option<Weak<RefCell<Dir>>>,
+}
+
+impl Dir {
+    fn root() -> Self {
+        Dir {
+            name: "ROOT".to_string(),
+            dirs: HashMap::new(),
+            files: vec![],
+            parent: None,
+        }
+    }
+    fn new(name: String, parent: Rc<RefCell<Dir>>) -> Dir {
+        Dir {
+            name,
+            dirs: HashMap::new(),
+            files: vec![],
+            parent: Some(Rc::downgrade(&parent)),
+        }
+    }
+
+    fn get_parent(&self) -> Option<Rc<RefCell<Dir>>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn add_dir(&mut self, dir: Rc<RefCell<Dir>>) -> Result<()> {
+        //        println!("adding dir `{}' to `{}'", dir.borrow().name, self.name);
+        let name = dir.borrow().name.clone();
+        match self.dirs.entry(name) {
+            Entry::Vacant(e) => {
+                e.insert(dir);
+                Ok(())
+            }
+            Entry::Occupied(e) => Err(anyhow!("inserted directory {} twice", e.key())),
+        }
+    }
+
+    fn dir_exists(&self, name: &str) -> bool {
+        self.dirs.contains_key(name)
+    }
+    fn get_dir(&self, name: &str) -> Option<Rc<RefCell<Dir>>> {
+        self.dirs.get(name).cloned()
+    }
+    fn add_file(&mut self, file: File) {
+        self.files.push(file)
+    }
+
+    fn check_sizes(&self, results: &mut Vec<(String, u64)>, threshold: u64) -> u64 {
+        let mut my_size: u64 = 0;
+        for d in self.dirs.values() {
+            my_size += d.borrow().check_sizes(results, threshold);
+        }
+        for f in &self.files {
+            my_size += f.size;
+        }
+        if my_size <= threshold {
+            results.push((self.name.to_owned(), my_size));
+        }
+        my_size
+    }
+    fn dump(&self, level: usize) {
+        event!(
+            Level::TRACE,
+            "{}directory {}",
+            "    ".repeat(level),
+            self.name
+        );
+        for (_, dir) in self.dirs.iter() {
+            dir.borrow().dump(level + 1);
+        }
+        for f in &self.files {
+            f.dump(level + 1);
+        }
+    }
+}
+
+/// One line of the terminal session.
+enum Output<'a> {
+    Cd(&'a str),
+    Ls,
+    Dir(&'a str),
+    File(u64, &'a str),
+}
+
+fn parse_line<'a>(line: &Line<'a>) -> Result<Output<'a>, ScanError> {
+    let tokens: Vec<Token> = line.tokens().collect();
+    match tokens.as_slice() {
+        [dollar, cmd, args @ ..] if dollar.text == "$" => match (cmd.text, args) {
+            ("cd", [arg]) => Ok(Output::Cd(arg.text)),
+            ("ls", []) => Ok(Output::Ls),
+            _ => Err(cmd.error(format!("unknown command: {}", cmd.text))),
+        },
+        [dir, name] if dir.text == "dir" => Ok(Output::Dir(name.text)),
+        [size, name] => Ok(Output::File(size.parse()?, name.text)),
+        _ => Err(line
+            .token()
+            .error("expected a command, a directory or a file")),
+    }
+}
+
+/// Whether the lines after `output` are part of a directory listing.
+fn listing_after(listing: bool, line: &Line, output: &Output) -> Result<bool, ScanError> {
+    match output {
+        Output::Cd(_) => Ok(false),
+        Output::Ls => Ok(true),
+        Output::Dir(_) | Output::File(..) if listing => Ok(true),
+        _ => Err(line.token().error("expected a command")),
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    let mut listing = false;
+    crate::lint::each_line(input, |line| {
+        listing = listing_after(listing, line, &parse_line(line)?)?;
+        Ok(())
+    })
+}
+
+/// A terminal session exploring a random tree of `scale` files and
+/// directories, listing each directory once.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    struct Entry {
+        name: String,
+        // directories have no size of their own
+        size: Option<u32>,
+        children: Vec<usize>,
+    }
+
+    let mut entries = vec![Entry {
+        name: "/".to_string(),
+        size: None,
+        children: vec![],
+    }];
+    let mut dirs = vec![0];
+    for n in 1..scale.max(1) {
+        let parent = *dirs.choose(rng).unwrap();
+        let stem: String = (0..rng.gen_range(1..=6))
+            .map(|_| rng.gen_range('a'..='z'))
+            .collect();
+        let (name, size) = if rng.gen_bool(0.25) {
+            dirs.push(n);
+            (format!("{stem}{n}"), None)
+        } else {
+            (
+                format!("{stem}{n}.dat"),
+                Some(rng.gen_range(1_000..=300_000)),
+            )
+        };
+        entries.push(Entry {
+            name,
+            size,
+            children: vec![],
+        });
+        entries[parent].children.push(n);
+    }
+
+    fn explore(entries: &[Entry], dir: usize, out: &mut String) {
+        out.push_str("$ ls\n");
+        for child in entries[dir].children.iter().map(|&c| &entries[c]) {
+            match child.size {
+                Some(size) => writeln!(out, "{size} {}", child.name).unwrap(),
+                None => writeln!(out, "dir {}", child.name).unwrap(),
+            }
+        }
+        for &child in entries[dir].children.iter() {
+            if entries[child].size.is_none() {
+                writeln!(out, "$ cd {}", entries[child].name).unwrap();
+                explore(entries, child, out);
+                out.push_str("$ cd ..\n");
+            }
+        }
+    }
+
+    let mut out = "$ cd /\n".to_string();
+    explore(&entries, 0, &mut out);
+    out
+}
+
+fn parse(input: &str) -> Result<Rc<RefCell<Dir>>> {
+    let root = Rc::new(RefCell::new(Dir::root()));
+    let mut current_dir = root.clone();
+    let mut listing = false;
+
+    for line in Scanner::new(input).lines() {
+        event!(Level::TRACE, "LINE: {}", line.text);
+        let output = parse_line(&line)?;
+        listing = listing_after(listing, &line, &output)?;
+        match output {
+            Output::Cd("/") => current_dir = root.clone(),
+            Output::Cd("..") => {
+                let p = current_dir.clone();
+                if let Some(p) = p.borrow().get_parent() {
+                    current_dir = p;
+                };
+            }
+            Output::Cd(arg) => {
+                let child = current_dir.borrow().get_dir(arg);
+                if let Some(child) = child {
+                    current_dir = child;
+                } else {
+                    let this_ptr = current_dir.clone();
+                    let new_dir = Rc::new(RefCell::new(Dir::new(arg.to_owned(), this_ptr.clone())));
+                    this_ptr
+                        .borrow_mut()
+                        .add_dir(new_dir.clone())
+                        .with_context(|| format!("line {}", line.number))?;
+                    current_dir = new_dir;
+                }
+            }
+            Output::Ls => {}
+            Output::Dir(name) => {
+                let dir = Dir::new(name.to_string(), current_dir.clone());
+                let mut cd = current_dir.borrow_mut();
+                if !cd.dir_exists(name) {
+                    cd.add_dir(Rc::new(RefCell::new(dir)))?;
+                }
+            }
+            Output::File(size, name) => {
+                let file = File::new(name, size /* current_dir.clone() */);
+                let mut cd = current_dir.borrow_mut();
+                cd.add_file(file);
+            }
+        }
+    }
+    Ok(root)
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "total size of directories of at most 100000"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d7/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let root: Rc<RefCell<Dir>> = parsed.take();
+        let mut dirs: Vec<(String, u64)> = vec![];
+        root.borrow().check_sizes(&mut dirs, 100_000);
+
+        Ok(dirs.iter().map(|d| d.1).sum::<u64>().into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "size of the smallest directory that frees enough space"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d7/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let root: Rc<RefCell<Dir>> = parsed.take();
+        root.borrow().dump(0);
+
+        let mut dirs: Vec<(String, u64)> = vec![];
+        let root_size = root.borrow().check_sizes(&mut dirs, u64::MAX);
+
+        let free_space = 70_000_000u64.saturating_sub(root_size);
+        let needed_space = 30_000_000u64.saturating_sub(free_space);
+        if needed_space == 0 {
+            event!(Level::DEBUG, "already have enough space");
+            Ok(0.into())
+        } else {
+            let (name, size) = dirs
+                .iter()
+                .filter(|v| v.1 >= needed_space)
+                .min_by(|x, y| x.1.cmp(&y.1))
+                .ok_or_else(|| anyhow!("no directory frees {needed_space} bytes"))?;
+            event!(Level::DEBUG, "best: {name}");
+            Ok((*size).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    example_tests!(7: 95437, 24933642);
+
+    #[test]
+    fn test1() {
+        let data = r"$ cd /
+        $ ls
+        dir a
+        14848514 b.txt
+        8504156 c.dat
+        dir d
+        $ cd a
+        $ ls
+        dir e
+        29116 f
+        2557 g
+        62596 h.lst
+        $ cd e
+        $ ls
+        584 i
+        $ cd ..
+        $ cd ..
+        $ cd d
+        $ ls
+        4060174 j
+        8033020 d.log
+        5626152 d.ext
+        7214296 k";
+
+        let root = super::parse(data).unwrap();
+        //root.borrow().dump(0);
+
+        //        assert_eq!(root.borrow().size(), 48381165);
+        let mut dirs: Vec<(String, u64)> = vec![];
+
+        root.borrow().check_sizes(&mut dirs, 100_000);
+        dirs.sort();
+        assert_eq!(dirs, vec![("a".to_string(), 94853), ("e".to_string(), 584)]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::generate::Rng;
+use crate::geometry::{Grid, Point};
+use crate::scanner::ScanError;
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::Result;
+use rand::Rng as _;
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 8,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    crate::lint::grid(input, |c| c.is_ascii_digit())
+}
+
+/// A `scale` by `scale` grid of tree heights.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let side = scale.max(1);
+    let mut out = String::with_capacity(side * (side + 1));
+    for _ in 0..side {
+        out.extend((0..side).map(|_| rng.gen_range('0'..='9')));
+        out.push('\n');
+    }
+    out
+}
+
+fn is_visible(trees: &Grid<u32>, columns: &Grid<u32>, p: Point) -> bool {
+    let (i, j) = (p.y as usize, p.x as usize);
+    let (row, column) = (trees.row(i), columns.row(j));
+    let my_val = row[j];
+    row[(j + 1)..].iter().all(|x| *x < my_val)
+        || row[0..j].iter().all(|x| *x < my_val)
+        || column[(i + 1)..].iter().all(|x| *x < my_val)
+        || column[0..i].iter().all(|x| *x < my_val)
+}
+
+fn part1(trees: &Grid<u32>, columns: &Grid<u32>) -> usize {
+    // trees on the edge have nothing in the way on at least one side
+    trees
+        .points()
+        .filter(|p| is_visible(trees, columns, *p))
+        .count()
+}
+
+fn score_plane(my_val: u32, plane: &[u32], i: usize) -> (u32, u32) {
+    // moving right
+    let mut right_score = 0;
+    for tree in plane[(i + 1)..].iter() {
+        right_score += 1;
+        if *tree >= my_val {
+            break;
+        }
+    }
+    // moving left
+    let mut left_score = 0;
+    for tree in plane[0..i].iter().rev() {
+        left_score += 1;
+        if *tree >= my_val {
+            break;
+        }
+    }
+    (right_score, left_score)
+}
+
+fn score(trees: &Grid<u32>, columns: &Grid<u32>, p: Point) -> u32 {
+    let my_val = trees[p];
+    let (right, left) = score_plane(my_val, trees.row(p.y as usize), p.x as usize);
+    let (down, up) = score_plane(my_val, columns.row(p.x as usize), p.y as usize);
+    right * left * up * down
+}
+
+/// The trees, plus their transpose so columns can be read as slices too.
+fn parse(data: &str) -> Result<(Grid<u32>, Grid<u32>)> {
+    let trees = Grid::parse(&mut Scanner::new(data), |c| c.to_digit(10))?;
+    let columns = trees.transposed();
+    Ok((trees, columns))
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "trees visible from outside the grid"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d8/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let (trees, columns): (Grid<u32>, Grid<u32>) = parsed.take();
+        Ok(part1(&trees, &columns).into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "highest scenic score"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d8/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let (trees, columns): (Grid<u32>, Grid<u32>) = parsed.take();
+        let max = trees
+            .points()
+            .map(|p| score(&trees, &columns, p))
+            .max()
+            .unwrap_or(0);
+
+        Ok(max.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    example_tests!(8: 21, 8);
+
+    #[test]
+    fn test2() {
+        let (trees, columns) = parse("30373\n25512\n65332\n33549\n35390").unwrap();
+        assert_eq!(score(&trees, &columns, Point::new(2, 1)), 4);
+        assert_eq!(part1(&trees, &columns), 21);
+    }
+}
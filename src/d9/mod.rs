@@ -0,0 +1,212 @@
+use crate::generate::Rng;
+use crate::geometry::{Point, Vector};
+use crate::scanner::{Line, ScanError, Token};
+use crate::{Answer, Parsed, PuzzleRun, Scanner};
+use anyhow::Result;
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::str::FromStr;
+use strum_macros::EnumString;
+use tracing::{enabled, event, Level};
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: 9,
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+#[derive(Debug, PartialEq, EnumString)]
+enum Dir {
+    U,
+    D,
+    R,
+    L,
+}
+
+impl Dir {
+    fn step(&self) -> Vector {
+        match self {
+            Dir::U => Vector::UP,
+            Dir::D => Vector::DOWN,
+            Dir::R => Vector::RIGHT,
+            Dir::L => Vector::LEFT,
+        }
+    }
+}
+
+/// Where a knot at `from` moves to once the knot ahead of it is at `next`.
+fn follow(from: Point, next: Point) -> Point {
+    let gap = next - from;
+    if gap.chebyshev() <= 1 {
+        from
+    } else {
+        from + gap.signum()
+    }
+}
+
+fn parse_instruction(line: &Line) -> Result<(Dir, u32), ScanError> {
+    let tokens: Vec<Token> = line.tokens().collect();
+    let [dir, n] = tokens.as_slice() else {
+        return Err(line.error("expected a direction and a step count"));
+    };
+    let dir = Dir::from_str(dir.text)
+        .map_err(|_| dir.error(format!("invalid direction: {}", dir.text)))?;
+    Ok((dir, n.parse()?))
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    crate::lint::each_line(input, parse_instruction)
+}
+
+/// `scale` moves of the head.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..scale.max(1) {
+        let dir = *['U', 'D', 'L', 'R'].choose(rng).unwrap();
+        writeln!(out, "{dir} {}", rng.gen_range(1..=20)).unwrap();
+    }
+    out
+}
+
+fn parse(input: &str) -> Result<Vec<(Dir, u32)>, ScanError> {
+    Scanner::new(input)
+        .lines()
+        .map(|line| parse_instruction(&line))
+        .collect()
+}
+
+fn part1(moves: &[(Dir, u32)]) -> usize {
+    let (mut head, mut tail) = (Point::ORIGIN, Point::ORIGIN);
+    let mut seen: HashSet<Point> = Default::default();
+    seen.insert(tail);
+
+    for (dir, n) in moves.iter() {
+        for _ in 0..*n {
+            head += dir.step();
+            tail = follow(tail, head);
+            //println!("head: {:?}\ttail: {:?}", head, tail);
+            seen.insert(tail);
+        }
+    }
+    seen.len()
+}
+fn part2(moves: &[(Dir, u32)]) -> usize {
+    let mut knots = [Point::ORIGIN; 10];
+    let mut seen: HashSet<Point> = Default::default();
+    seen.insert(knots[9]);
+
+    for (dir, n) in moves.iter() {
+        event!(Level::TRACE, "instr: {dir:?} {n}");
+        for _ in 0..*n {
+            knots[0] += dir.step();
+            for i in 1..knots.len() {
+                knots[i] = follow(knots[i], knots[i - 1]);
+            }
+            dump(&knots);
+            seen.insert(knots[9]);
+        }
+    }
+    seen.len()
+}
+
+fn dump(knots: &[Point; 10]) {
+    if !enabled!(Level::TRACE) {
+        return;
+    }
+    let mut picture = String::new();
+    // up is toward negative y, so the top row of the picture is y = -5
+    for row in -5..=0 {
+        picture.push('\n');
+        for col in 0..6 {
+            match knots.iter().position(|k| *k == Point::new(col, row)) {
+                Some(p) => picture.push_str(&format!("{p} ")),
+                None => picture.push_str(". "),
+            }
+        }
+    }
+    event!(Level::TRACE, "knots:{picture}");
+}
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "positions visited by the tail of a two-knot rope"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d9/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(part1(&parsed.take::<Vec<(Dir, u32)>>()).into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "positions visited by the tail of a ten-knot rope"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d9/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example-part2.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        Ok(part2(&parsed.take::<Vec<(Dir, u32)>>()).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    example_tests!(9: 13, 36);
+
+    #[test]
+    fn test_part2() {
+        let data = r"R 4
+        U 4
+        L 3
+        D 1
+        R 4
+        D 1
+        L 5
+        R 2";
+
+        // the tail never moves on the short example
+        assert_eq!(super::part2(&super::parse(data).unwrap()), 1);
+    }
+}
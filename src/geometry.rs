@@ -0,0 +1,311 @@
+use crate::scanner::{ScanError, Scanner};
+use ndarray::{Array2, ArrayView1};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A position on the plane.  Grids put `x` in the column and `y` in the row,
+/// so `y` grows downward there; days that want `y` up just treat it that way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The difference between two points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector {
+    pub dx: i64,
+    pub dy: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(&self, other: Point) -> u64 {
+        (other - *self).manhattan()
+    }
+
+    /// The four points sharing an edge with this one.
+    pub fn neighbors4(self) -> impl Iterator<Item = Point> {
+        Vector::ORTHOGONAL.into_iter().map(move |v| self + v)
+    }
+
+    /// The eight points sharing an edge or corner with this one.
+    pub fn neighbors8(self) -> impl Iterator<Item = Point> {
+        Vector::ALL.into_iter().map(move |v| self + v)
+    }
+}
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Vector {
+    pub const UP: Vector = Vector::new(0, -1);
+    pub const DOWN: Vector = Vector::new(0, 1);
+    pub const LEFT: Vector = Vector::new(-1, 0);
+    pub const RIGHT: Vector = Vector::new(1, 0);
+    pub const ORTHOGONAL: [Vector; 4] = [Self::UP, Self::DOWN, Self::LEFT, Self::RIGHT];
+    pub const ALL: [Vector; 8] = [
+        Vector::new(-1, -1),
+        Self::UP,
+        Vector::new(1, -1),
+        Self::LEFT,
+        Self::RIGHT,
+        Vector::new(-1, 1),
+        Self::DOWN,
+        Vector::new(1, 1),
+    ];
+
+    pub const fn new(dx: i64, dy: i64) -> Self {
+        Self { dx, dy }
+    }
+
+    pub fn manhattan(&self) -> u64 {
+        self.dx.unsigned_abs() + self.dy.unsigned_abs()
+    }
+
+    /// The number of king moves this vector spans.
+    pub fn chebyshev(&self) -> u64 {
+        self.dx.unsigned_abs().max(self.dy.unsigned_abs())
+    }
+
+    /// A vector of at most one step along each axis in the same direction.
+    pub fn signum(&self) -> Vector {
+        Vector::new(self.dx.signum(), self.dy.signum())
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Point {
+        Point::new(self.x + v.dx, self.y + v.dy)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, v: Vector) -> Point {
+        self + -v
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, v: Vector) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.dx + other.dx, self.dy + other.dy)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.dx, -self.dy)
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, n: i64) -> Vector {
+        Vector::new(self.dx * n, self.dy * n)
+    }
+}
+
+/// A rectangular grid of cells addressed by `Point`, with the origin at the
+/// top left.  Cells are stored row by row, so rows can be borrowed as slices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parse the next blank-line-separated block of `scanner` as a grid.
+    pub fn parse<F>(scanner: &mut Scanner, cell: F) -> Result<Self, ScanError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        // the scanner builds its arrays in row-major order
+        Ok(Self {
+            cells: scanner.grid(cell)?,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.ncols()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.nrows()
+    }
+
+    fn index_of(&self, p: Point) -> Option<(usize, usize)> {
+        let (row, col) = (usize::try_from(p.y).ok()?, usize::try_from(p.x).ok()?);
+        (row < self.height() && col < self.width()).then_some((row, col))
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.index_of(p).is_some()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index_of(p).map(|i| &mut self.cells[i])
+    }
+
+    /// The orthogonal neighbors of `p` that lie inside the grid.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbors4().filter(|n| self.contains(*n))
+    }
+
+    /// The orthogonal and diagonal neighbors of `p` that lie inside the grid.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbors8().filter(|n| self.contains(*n))
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width() as i64, self.height() as i64);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell with its point, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .indexed_iter()
+            .map(|((row, col), cell)| (Point::new(col as i64, row as i64), cell))
+    }
+
+    /// The first point, row by row, whose cell matches `pred`.
+    pub fn position<P: FnMut(&T) -> bool>(&self, mut pred: P) -> Option<Point> {
+        self.iter().find(|(_, c)| pred(c)).map(|(p, _)| p)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        self.cells
+            .row(y)
+            .to_slice()
+            .expect("grid rows are contiguous")
+    }
+
+    pub fn column(&self, x: usize) -> ArrayView1<'_, T> {
+        self.cells.column(x)
+    }
+
+    /// Render the grid as text, one line per row.
+    pub fn render<F: FnMut(&T) -> char>(&self, mut glyph: F) -> String {
+        let mut text = String::with_capacity(self.height() * (self.width() + 1));
+        for row in self.cells.rows() {
+            text.extend(row.iter().map(&mut glyph));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            cells: Array2::from_elem((height, width), fill),
+        }
+    }
+
+    /// A grid over `cells`, indexed `[row, column]`.
+    pub fn from_array(cells: Array2<T>) -> Self {
+        Self {
+            cells: cells.as_standard_layout().into_owned(),
+        }
+    }
+
+    /// The grid flipped about its diagonal, so columns become rows.
+    pub fn transposed(&self) -> Self {
+        Self::from_array(self.cells.t().to_owned())
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{p} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p} is outside the grid"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let (a, b) = (Point::new(1, 2), Point::new(-2, 6));
+        assert_eq!(b - a, Vector::new(-3, 4));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!((b - a).chebyshev(), 4);
+        assert_eq!((b - a).signum(), Vector::new(-1, 1));
+        assert_eq!(a + Vector::UP * 3, Point::new(1, -1));
+        assert_eq!(Point::ORIGIN.neighbors8().count(), 8);
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::parse(&mut Scanner::new("abc\ndef"), Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 'f');
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).to_vec(), vec!['b', 'e']);
+        assert_eq!(grid.position(|c| *c == 'e'), Some(Point::new(1, 1)));
+
+        let corner: Vec<_> = grid.neighbors4(Point::ORIGIN).collect();
+        assert_eq!(corner, vec![Point::new(0, 1), Point::new(1, 0)]);
+        assert_eq!(grid.neighbors8(Point::new(1, 0)).count(), 5);
+
+        let t = grid.transposed();
+        assert_eq!(t.row(2), &['c', 'f']);
+        assert_eq!(t.render(|c| *c), "ad\nbe\ncf\n");
+
+        grid[Point::ORIGIN] = 'z';
+        assert_eq!(grid.render(|c| *c), "zbc\ndef\n");
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{Answer, Parsed, PuzzleRun};
+use anyhow::{anyhow, Context, Result};
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Where the runner reads a day's puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// A file named on the command line, or found in the inputs directory.
+    File(PathBuf),
+    /// Standard input, selected with `--input -`.
+    Stdin,
+    /// The example from the puzzle text.
+    Example,
+    /// Whatever the day's `input_data` provides.
+    Default,
+}
+
+impl InputSource {
+    /// Pick the input for `day`.  An explicit `--input` wins, then
+    /// `--example`, then `<inputs_dir>/day<N>.txt` if it exists, and
+    /// finally the day's own default.
+    pub fn resolve(
+        input: Option<&Path>,
+        example: bool,
+        inputs_dir: Option<&Path>,
+        day: u8,
+    ) -> Self {
+        if let Some(path) = input {
+            return if path == Path::new("-") {
+                InputSource::Stdin
+            } else {
+                InputSource::File(path.to_owned())
+            };
+        }
+        if example {
+            return InputSource::Example;
+        }
+        if let Some(dir) = inputs_dir {
+            let path = dir.join(format!("day{day}.txt"));
+            if path.is_file() {
+                return InputSource::File(path);
+            }
+        }
+        InputSource::Default
+    }
+
+    /// The file this source reads for `day`, if it reads one.  Days read
+    /// their default input from `src/dN/input.txt`.
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Default => Some(PathBuf::from(format!("src/d{day}/input.txt"))),
+            InputSource::Stdin | InputSource::Example => None,
+        }
+    }
+}
+
+impl InputSource {
+    /// Solve an input parsed from this source: the example with the
+    /// puzzle's `solve_example`, anything else with `solve`.
+    pub fn solve(&self, puzzle: &dyn PuzzleRun, parsed: Parsed) -> Result<Answer> {
+        match self {
+            InputSource::Example => puzzle.solve_example(parsed),
+            _ => puzzle.solve(parsed),
+        }
+    }
+}
+
+impl std::fmt::Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::Example => write!(f, "example"),
+            InputSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// A resolved source plus any text read up front.  Files and stdin are read
+/// once and shared by every part of the day; stdin can't be read twice.
+pub struct DayInput {
+    source: InputSource,
+    text: Option<String>,
+}
+
+impl DayInput {
+    pub fn load(source: InputSource) -> Result<Self> {
+        let text = match &source {
+            InputSource::File(path) => Some(
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?,
+            ),
+            InputSource::Stdin => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .context("reading stdin")?;
+                Some(text)
+            }
+            InputSource::Example | InputSource::Default => None,
+        };
+        Ok(Self { source, text })
+    }
+
+    /// The input text to lend to `puzzle`'s `run`.  Text read up front is
+    /// borrowed; the day's default input is read for the call and dropped
+    /// with the result.
+    pub fn for_puzzle<'a>(&'a self, puzzle: &'a dyn PuzzleRun) -> Result<Cow<'a, str>> {
+        match (&self.source, &self.text) {
+            (_, Some(text)) => Ok(Cow::Borrowed(text)),
+            (InputSource::Example, None) => puzzle
+                .example_data()
+                .map(Cow::Borrowed)
+                .ok_or_else(|| anyhow!("no example input for part {}", puzzle.part())),
+            _ => puzzle.input_data().map(Cow::Owned),
+        }
+    }
+
+    /// Parse and solve this input with `puzzle`.
+    pub fn run(&self, puzzle: &dyn PuzzleRun) -> Result<Answer> {
+        let text = self.for_puzzle(puzzle)?;
+        self.source.solve(puzzle, puzzle.parse(&text)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve_order() {
+        let file = Path::new("mine.txt");
+        assert_eq!(
+            InputSource::resolve(Some(file), true, Some(Path::new("src")), 1),
+            InputSource::File(file.to_owned())
+        );
+        assert_eq!(
+            InputSource::resolve(Some(Path::new("-")), false, None, 1),
+            InputSource::Stdin
+        );
+        assert_eq!(
+            InputSource::resolve(None, true, Some(Path::new("src")), 1),
+            InputSource::Example
+        );
+        // no src/day1.txt, so the inputs directory is skipped
+        assert_eq!(
+            InputSource::resolve(None, false, Some(Path::new("src")), 1),
+            InputSource::Default
+        );
+    }
+
+    #[test]
+    fn test_path() {
+        let file = Path::new("mine.txt");
+        assert_eq!(
+            InputSource::File(file.to_owned()).path(3),
+            Some(file.to_owned())
+        );
+        assert_eq!(
+            InputSource::Default.path(14),
+            Some(PathBuf::from("src/d14/input.txt"))
+        );
+        assert_eq!(InputSource::Stdin.path(14), None);
+        assert_eq!(InputSource::Example.path(14), None);
+    }
+
+    #[test]
+    fn test_for_puzzle() {
+        let runs = (crate::day(6).unwrap().runs)();
+        let puzzle = runs[0].as_ref();
+
+        let example = DayInput::load(InputSource::Example).unwrap();
+        let text = example.for_puzzle(puzzle).unwrap();
+        assert!(matches!(text, Cow::Borrowed(_)));
+        assert_eq!(text, puzzle.example_data().unwrap());
+
+        let default = DayInput::load(InputSource::Default).unwrap();
+        let text = default.for_puzzle(puzzle).unwrap();
+        assert!(matches!(text, Cow::Owned(_)));
+        assert_eq!(text, puzzle.input_data().unwrap());
+    }
+}
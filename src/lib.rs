@@ -1,44 +1,201 @@
-use std::io::{self, prelude::*};
+use anyhow::{anyhow, Context, Result};
+use std::any::Any;
+use std::path::Path;
+use std::sync::OnceLock;
 
-pub(crate) trait Puzzle {
-    fn solve<R: BufRead, W: Write>(&self, scan: &mut Scanner<R>, w: &mut W) -> io::Result<()>;
+/// Check each part of a day against the answers to its example input, in
+/// part order, e.g. `example_tests!(6: 7, 19);`.  Attributes such as
+/// `#[ignore = "why"]` go before the day and apply to the generated test.
+#[cfg(test)]
+macro_rules! example_tests {
+    ($(#[$attr:meta])* $day:literal: $($answer:expr),+ $(,)?) => {
+        #[test]
+        $(#[$attr])*
+        fn examples() {
+            crate::check_examples($day, &[$(crate::Answer::from($answer)),+]);
+        }
+    };
 }
 
-#[allow(dead_code)]
-pub(crate) fn run<P: Puzzle>(puzzle: P) -> io::Result<()> {
-    let (stdin, stdout) = (io::stdin(), io::stdout());
-    let mut scan = Scanner::new(io::BufReader::new(stdin.lock()));
-    let mut out = io::BufWriter::new(stdout.lock());
-    puzzle.solve(&mut scan, &mut out)
-}
-struct Scanner<R> {
-    reader: R,
-    buf_str: Vec<u8>,
-    buf_iter: std::str::SplitAsciiWhitespace<'static>,
+mod answer;
+// The day modules.  `new-day` writes its declaration here, the one line it
+// generates outside the new module; days register themselves with
+// `inventory`, so nothing else lists them.
+mod d1;
+mod d10;
+mod d11;
+mod d12;
+mod d13;
+mod d14;
+mod d15;
+mod d16;
+mod d2;
+mod d3;
+mod d4;
+mod d5;
+mod d6;
+mod d7;
+mod d8;
+mod d9;
+pub mod generate;
+pub mod geometry;
+pub mod input;
+pub mod lint;
+pub mod scanner;
+
+pub use answer::Answer;
+pub use scanner::Scanner;
+
+pub trait PuzzleRun {
+    /// Which part of the day's puzzle this run answers.
+    fn part(&self) -> u8;
+
+    /// A short description of the answer, used to label results.
+    fn description(&self) -> &str;
+
+    /// The day's puzzle input, read afresh on each call.  The caller owns
+    /// it and lends it to `parse`.
+    fn input_data(&self) -> anyhow::Result<String>;
+
+    /// The example input from the puzzle text, if this run has one.
+    fn example_data(&self) -> Option<&str> {
+        None
+    }
+
+    /// Read `input` into the form `solve` works from.  Kept apart from
+    /// solving so that the benchmark can time the two separately.
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed>;
+
+    /// Answer the puzzle from what `parse` made of the input.
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer>;
+
+    /// Answer the example from what `parse` made of it.  Most days solve it
+    /// like any other input; a day whose example asks with different
+    /// parameters, such as the row d15 looks at, overrides this.
+    fn solve_example(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        self.solve(parsed)
+    }
+
+    fn run(&self, input: &str) -> anyhow::Result<Answer> {
+        self.solve(self.parse(input)?)
+    }
+
+    fn run_example(&self, input: &str) -> anyhow::Result<Answer> {
+        self.solve_example(self.parse(input)?)
+    }
 }
 
-impl<R: BufRead> Scanner<R> {
-    fn new(reader: R) -> Self {
-        Self {
-            reader,
-            buf_str: vec![],
-            buf_iter: "".split_ascii_whitespace(),
+/// A part's parsed input, passed from its `parse` to its `solve`.  Each part
+/// picks its own type, so it's boxed here and unboxed by the part.
+pub struct Parsed(Box<dyn Any>);
+
+impl Parsed {
+    pub fn new<T: Any>(value: T) -> Self {
+        Self(Box::new(value))
+    }
+
+    /// The value `parse` stored.  Asking for another type is a bug in the
+    /// part, not a problem with the input, so it panics.
+    pub fn take<T: Any>(self) -> T {
+        match self.0.downcast() {
+            Ok(value) => *value,
+            Err(_) => panic!("parsed input is not a {}", std::any::type_name::<T>()),
         }
     }
+}
 
-    fn token<T: std::str::FromStr>(&mut self) -> T {
-        loop {
-            if let Some(token) = self.buf_iter.next() {
-                return token.parse().ok().expect("Failed parse");
-            }
-            self.buf_str.clear();
-            self.reader
-                .read_until(b'\n', &mut self.buf_str)
-                .expect("Failed read");
-            self.buf_iter = unsafe {
-                let slice = std::str::from_utf8_unchecked(&self.buf_str);
-                std::mem::transmute(slice.split_ascii_whitespace())
-            }
+fn read_file<P: AsRef<Path>>(filename: P) -> anyhow::Result<String> {
+    let filename = filename.as_ref();
+    std::fs::read_to_string(filename).with_context(|| format!("reading {}", filename.display()))
+}
+
+/// A registered day of the calendar.
+pub struct Day {
+    pub number: u8,
+    /// The runs for each of the day's parts, in part order.
+    pub runs: fn() -> Vec<Box<dyn PuzzleRun>>,
+    /// Check an input against the day's grammar, reporting every problem.
+    pub validate: fn(&str) -> Vec<scanner::ScanError>,
+    /// Generate a valid random input; what `scale` counts varies by day.
+    pub generate: fn(&mut generate::Rng, usize) -> String,
+}
+
+inventory::collect!(Day);
+
+/// Every registered day, in calendar order.  Each day module registers
+/// itself with `inventory::submit!`.
+pub fn days() -> &'static [&'static Day] {
+    static DAYS: OnceLock<Vec<&'static Day>> = OnceLock::new();
+    DAYS.get_or_init(|| {
+        let mut days: Vec<&'static Day> = inventory::iter::<Day>().collect();
+        days.sort_by_key(|d| d.number);
+        days
+    })
+}
+
+/// The error a new day's parts return until they're written, so that checks
+/// running every part can tell an unsolved part from a broken one.
+#[derive(Debug)]
+pub struct Unsolved;
+
+impl std::fmt::Display for Unsolved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not solved yet")
+    }
+}
+
+impl std::error::Error for Unsolved {}
+
+/// Look up a registered day by its number.
+pub fn day(number: u8) -> Option<&'static Day> {
+    days().iter().copied().find(|d| d.number == number)
+}
+
+/// Solve one part of a day's puzzle for `input`.
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer> {
+    let runs = (self::day(day)
+        .ok_or_else(|| anyhow!("day {day} is not registered"))?
+        .runs)();
+    let run = runs
+        .iter()
+        .find(|r| r.part() == part)
+        .ok_or_else(|| anyhow!("day {day} has no part {part}"))?;
+    run.run(input)
+}
+
+/// Day 2's parts, playing the game in `definition` rather than the puzzle's
+/// Rock-Paper-Scissors.  A definition lists the moves, what beats what, the
+/// scores and the guide's symbols; `src/d2/rps.txt` defines the puzzle's.
+pub fn game_runs(definition: &str) -> Result<Vec<Box<dyn PuzzleRun>>> {
+    d2::game_runs(definition)
+}
+
+/// A longer look at `input` than the answers give, for the days that have
+/// one.  `count` is how many entries to list: for day 1, the elves carrying
+/// the most, for day 2, the rounds explained, and for day 4, the overlapping
+/// pairs and elves.
+pub fn report(day: u8, input: &str, count: usize) -> Result<String> {
+    match day {
+        1 => Ok(d1::report(input, count)?.to_string()),
+        2 => d2::report(input, count),
+        4 => d4::report(input, count),
+        _ => Err(anyhow!("no report for this day")),
+    }
+}
+
+/// Run every part of `day` on its example input and compare the answers.
+#[cfg(test)]
+fn check_examples(day: u8, expected: &[Answer]) {
+    let runs = (self::day(day).expect("day is registered").runs)();
+    assert_eq!(runs.len(), expected.len(), "day {day}: one answer per part");
+    for (run, expected) in runs.iter().zip(expected) {
+        let part = run.part();
+        let input = run
+            .example_data()
+            .unwrap_or_else(|| panic!("day {day} part {part} has no example"));
+        match run.run_example(input) {
+            Ok(answer) => assert_eq!(answer, *expected, "day {day} part {part}"),
+            Err(e) => panic!("day {day} part {part}: {e:#}"),
         }
     }
 }
@@ -46,4 +203,66 @@ impl<R: BufRead> Scanner<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_registry() {
+        assert!(
+            days().windows(2).all(|w| w[0].number < w[1].number),
+            "each day is registered once"
+        );
+        for d in days() {
+            assert!((1..=25).contains(&d.number));
+            let parts: Vec<u8> = (d.runs)().iter().map(|r| r.part()).collect();
+            assert!(!parts.is_empty(), "day {} has no runs", d.number);
+            assert!(parts.windows(2).all(|w| w[0] < w[1]));
+            for run in (d.runs)() {
+                assert!(
+                    run.example_data().is_some(),
+                    "day {} part {} has no example",
+                    d.number,
+                    run.part()
+                );
+            }
+        }
+        assert!(day(25).is_none());
+    }
+
+    #[test]
+    fn test_solve() {
+        let signal = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(solve(6, 1, signal).unwrap(), Answer::Int(7));
+        assert_eq!(solve(6, 2, signal).unwrap(), Answer::Int(19));
+        assert!(solve(6, 3, signal).is_err());
+        assert!(solve(25, 1, signal).is_err());
+    }
+
+    #[test]
+    fn test_game_runs() {
+        let guide = include_str!("d2/example.txt");
+        let score = |runs: Vec<Box<dyn PuzzleRun>>| -> Vec<Answer> {
+            runs.iter().map(|r| r.run(guide).unwrap()).collect()
+        };
+        let rps = game_runs(include_str!("d2/rps.txt")).unwrap();
+        assert_eq!(score(rps), [Answer::Int(15), Answer::Int(12)]);
+        // with Spock, losing to Paper and beating Scissors both score 5 more
+        let rpsls = game_runs(include_str!("d2/rpsls.txt")).unwrap();
+        assert_eq!(score(rpsls), [Answer::Int(15), Answer::Int(20)]);
+        assert!(game_runs("move Rock 1\n").is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let elves = report(1, "1\n2\n\n4\n", 1).unwrap();
+        assert!(elves.starts_with("2 elves carry 7 calories"), "{elves}");
+        assert!(elves.contains("elf 2: 4"), "{elves}");
+        assert!(!elves.contains("elf 1: 3"), "{elves}");
+        let guide = report(2, "A Y\nB X\n", 2).unwrap();
+        assert!(guide.contains("round 2: against Paper"), "{guide}");
+        let sections = report(4, "2-4,6-8\n2-3,3-4\n", 5).unwrap();
+        assert!(
+            sections.contains("the first elf on line 2 and the second elf on line 2 overlap"),
+            "{sections}"
+        );
+        assert!(report(6, "abcd", 1).is_err());
+    }
 }
@@ -0,0 +1,74 @@
+use crate::scanner::{Line, ScanError, Scanner};
+use anyhow::{anyhow, Result};
+
+/// Check `input` against `day`'s grammar, returning every problem found.
+pub fn check(day: u8, input: &str) -> Result<Vec<ScanError>> {
+    let day = crate::day(day).ok_or_else(|| anyhow!("day {day} is not registered"))?;
+    let mut problems = (day.validate)(input);
+    problems.sort_by_key(|e| (e.line, e.column));
+    Ok(problems)
+}
+
+/// Parse each line of `input` with `parse`, collecting the errors.
+pub(crate) fn each_line<T, F>(input: &str, mut parse: F) -> Vec<ScanError>
+where
+    F: FnMut(&Line) -> Result<T, ScanError>,
+{
+    Scanner::new(input)
+        .lines()
+        .filter_map(|line| parse(&line).err())
+        .collect()
+}
+
+/// Check that `input` is a single rectangular grid of cells `valid` accepts.
+/// Unlike `Scanner::grid`, this reports every bad cell rather than the first.
+pub(crate) fn grid<F>(input: &str, valid: F) -> Vec<ScanError>
+where
+    F: Fn(char) -> bool,
+{
+    let mut scanner = Scanner::new(input);
+    let Some(rows) = scanner.next_group() else {
+        return vec![ScanError::new(1, 1, "expected a grid, found end of input")];
+    };
+    let width = rows[0].text.chars().count();
+    let mut problems = vec![];
+    for row in rows.iter() {
+        for (offset, c) in row.text.char_indices() {
+            if !valid(c) {
+                problems.push(row.error_at(offset, format!("unexpected `{c}`")));
+            }
+        }
+        let n = row.text.chars().count();
+        if n != width {
+            problems.push(row.error(format!("expected {width} columns, found {n}")));
+        }
+    }
+    problems.extend(trailing(&mut scanner));
+    problems
+}
+
+/// An error for the first non-blank line left in `scanner`, if any.
+pub(crate) fn trailing(scanner: &mut Scanner) -> Option<ScanError> {
+    scanner
+        .lines()
+        .find(|line| !line.text.trim().is_empty())
+        .map(|line| line.token().error("unexpected input after the end"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let problems = grid("12\n3x\n1\n\n5", |c| c.is_ascii_digit());
+        let at: Vec<_> = problems.iter().map(|e| (e.line, e.column)).collect();
+        assert_eq!(at, vec![(2, 2), (3, 1), (5, 1)]);
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check(6, "abcdefghijklmnop").unwrap().is_empty());
+        assert!(check(25, "").is_err());
+    }
+}
@@ -1,70 +1,657 @@
-use clap::{Parser, Subcommand};
-use std::path::Path;
+use advent2022::input::{DayInput, InputSource};
+use advent2022::{Answer, PuzzleRun};
+use anyhow::{bail, Context};
+use clap::error::ErrorKind;
+use clap::{ArgMatches, Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+use summary::PartResult;
+use tracing::{info_span, Level};
+use tracing_subscriber::fmt::format::FmtSpan;
+use verify::{Answers, Check};
 
-mod d1;
-mod d14;
-mod d15;
-mod d16;
-mod d2;
-mod d3;
-mod d4;
-mod d5;
-
-pub trait PuzzleRun {
-    fn input_data(&self) -> anyhow::Result<&str>;
-
-    fn run(&self, input: &str) -> String;
-}
-
-fn read_file<P: AsRef<Path>>(filename: P) -> anyhow::Result<&'static str> {
-    let data = std::fs::read_to_string(filename)?;
-    Ok(data.leak())
-}
+mod bench;
+mod scaffold;
+mod summary;
+mod verify;
+mod watch;
 
 #[derive(Parser)]
 struct Args {
-    /// Enable debug logging
-    #[arg(short, long, action = clap::ArgAction::Count)]
+    /// Log more detail: -d for info, -dd for debug, -ddd for trace
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
     debug: u8,
 
-    /// Which day's puzzles to run?
+    /// Which part of the puzzle to run
+    #[arg(short, long, global = true, value_enum, default_value_t = PartSelection::All)]
+    part: PartSelection,
+
+    /// How to print answers
+    #[arg(short, long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Read the puzzle input from this file, or `-` for stdin
+    #[arg(short, long, global = true)]
+    input: Option<PathBuf>,
+
+    /// Use the example input from the puzzle text
+    #[arg(short, long, global = true, conflicts_with = "input")]
+    example: bool,
+
+    /// Directory of inputs named dayN.txt, used when present
+    #[arg(long, global = true, env = "ADVENT_INPUTS")]
+    inputs_dir: Option<PathBuf>,
+
+    /// Re-run the day whenever its input file changes
+    #[arg(short, long, global = true)]
+    watch: bool,
+
+    /// Play day 2 by the game defined in this file instead of
+    /// Rock-Paper-Scissors; see src/d2/rps.txt for the format
+    #[arg(long, global = true)]
+    game: Option<PathBuf>,
+
     #[command(subcommand)]
-    day: Option<PuzzleDay>,
+    command: Option<Command>,
 }
+
 #[derive(Subcommand, Debug)]
-enum PuzzleDay {
-    Day1,
-    Day2,
-    Day3,
-    Day4,
-    Day5,
-    Day14,
-    Day15,
-    Day16,
+enum Command {
+    #[command(flatten)]
+    Day(PuzzleDay),
+
+    /// Run every day and part in parallel and print a summary
+    All,
+
+    /// Run every day and compare the answers with those recorded
+    Verify {
+        /// File of recorded answers
+        #[arg(long, default_value = "answers.txt")]
+        answers: PathBuf,
+
+        /// Record answers that aren't in the file yet instead of failing
+        #[arg(long)]
+        record: bool,
+    },
+
+    /// Time reading the input, parsing it and solving each part
+    Bench {
+        /// Only benchmark this day
+        day: Option<PuzzleDay>,
+
+        /// How many times to run each part
+        #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+
+        /// Also write the results to this .csv or .json file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Check an input against the day's grammar without solving it
+    Lint {
+        day: PuzzleDay,
+
+        /// The input to check, or `-` for stdin; defaults to the day's input
+        file: Option<PathBuf>,
+    },
+
+    /// Describe an input in more detail than the answers do
+    Report {
+        day: PuzzleDay,
+
+        /// How many entries to list: the top elves for day 1, the rounds
+        /// explained for day 2, the overlapping pairs and elves for day 4
+        #[arg(short = 'k', long, default_value_t = 3)]
+        count: usize,
+    },
+
+    /// Write a random input for stress testing
+    Generate {
+        day: PuzzleDay,
+
+        /// How big an input to make: lines, grid side, valves and so on,
+        /// depending on the day
+        #[arg(short, long, default_value_t = 1000)]
+        scale: usize,
+
+        /// Seed for the generator; the same seed makes the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Write the input to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Start a new day: write its module skeleton and input placeholders,
+    /// and declare the module in src/lib.rs
+    NewDay {
+        /// The day's number
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    /// One line per part, or a table when running every day
+    Text,
+    /// One JSON record per line for each part
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum PartSelection {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    All,
+}
+
+impl PartSelection {
+    fn includes(&self, part: u8) -> bool {
+        match self {
+            PartSelection::One => part == 1,
+            PartSelection::Two => part == 2,
+            PartSelection::All => true,
+        }
+    }
 }
+/// A registered day, named `dayN` on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PuzzleDay(u8);
 
-fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
+impl PuzzleDay {
+    /// Every registered day, in calendar order.
+    fn all() -> impl Iterator<Item = PuzzleDay> {
+        advent2022::days().iter().map(|d| PuzzleDay(d.number))
+    }
+
+    fn number(&self) -> u8 {
+        self.0
+    }
+
+    fn runs(&self) -> Vec<Box<dyn PuzzleRun>> {
+        let day = advent2022::day(self.number()).expect("every PuzzleDay is registered");
+        (day.runs)()
+    }
+}
+
+impl std::fmt::Display for PuzzleDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day{}", self.0)
+    }
+}
+
+impl std::str::FromStr for PuzzleDay {
+    type Err = String;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix("day")
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| advent2022::day(n).is_some())
+            .map(PuzzleDay)
+            .ok_or_else(|| {
+                let days: Vec<String> = PuzzleDay::all().map(|d| d.to_string()).collect();
+                format!("expected one of {}", days.join(", "))
+            })
+    }
+}
+
+// Days register themselves with the library, so the `dayN` subcommands are
+// built from the registry rather than derived.
+impl clap::FromArgMatches for PuzzleDay {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        let name = matches
+            .subcommand_name()
+            .ok_or_else(|| clap::Error::raw(ErrorKind::MissingSubcommand, "expected a day"))?;
+        name.parse()
+            .map_err(|e| clap::Error::raw(ErrorKind::InvalidSubcommand, e))
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
+
+impl Subcommand for PuzzleDay {
+    fn augment_subcommands(cmd: clap::Command) -> clap::Command {
+        cmd.subcommands(PuzzleDay::all().map(|day| {
+            clap::Command::new(day.to_string()).about(format!("Run day {}", day.number()))
+        }))
+    }
+
+    fn augment_subcommands_for_update(cmd: clap::Command) -> clap::Command {
+        Self::augment_subcommands(cmd)
+    }
+
+    fn has_subcommand(name: &str) -> bool {
+        name.parse::<PuzzleDay>().is_ok()
+    }
+}
+
+fn main() -> ExitCode {
     let args = Args::parse();
+    init_tracing(args.debug);
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn init_tracing(debug: u8) {
+    let level = match debug {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    // logs go to stderr so that stdout only carries answers
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+fn run(args: &Args) -> anyhow::Result<()> {
+    if args.watch && !matches!(args.command, Some(Command::Day(_))) {
+        bail!("--watch only works when running a single day");
+    }
+    if args.game.is_some() && !matches!(args.command, Some(Command::Day(PuzzleDay(2)))) {
+        bail!("--game only works when running day 2");
+    }
+    match &args.command {
+        Some(Command::Day(day)) if args.watch => watch(args, *day),
+        Some(Command::Day(day)) => run_day(args, *day),
+        Some(Command::All) | None => run_all(args),
+        Some(Command::Verify { answers, record }) => verify(args, answers, *record),
+        Some(Command::Bench {
+            day,
+            iterations,
+            output,
+        }) => bench(args, *day, *iterations, output.as_deref()),
+        Some(Command::Lint { day, file }) => lint(args, *day, file.as_deref()),
+        Some(Command::Report { day, count }) => report(args, *day, *count),
+        Some(Command::Generate {
+            day,
+            scale,
+            seed,
+            output,
+        }) => generate(*day, *scale, *seed, output.as_deref()),
+        Some(Command::NewDay { day }) => new_day(*day),
+    }
+}
 
-    let puzzles = match args.day {
-        Some(PuzzleDay::Day1) => d1::get_runs(),
-        Some(PuzzleDay::Day2) => d2::get_runs(),
-        Some(PuzzleDay::Day3) => d3::get_runs(),
-        Some(PuzzleDay::Day4) => d4::get_runs(),
-        Some(PuzzleDay::Day5) => d5::get_runs(),
-        Some(PuzzleDay::Day14) => d14::get_runs(),
-        Some(PuzzleDay::Day15) => d15::get_runs(),
-        Some(PuzzleDay::Day16) => d16::get_runs(),
-        _ => {
-            println!("not found: {:?}", args.day);
-            panic!()
+fn input_source(args: &Args, day: PuzzleDay) -> InputSource {
+    InputSource::resolve(
+        args.input.as_deref(),
+        args.example,
+        args.inputs_dir.as_deref(),
+        day.number(),
+    )
+}
+
+/// The parts of `day` to run, playing day 2 by `--game` when it's given.
+fn day_runs(args: &Args, day: PuzzleDay) -> anyhow::Result<Vec<Box<dyn PuzzleRun>>> {
+    match &args.game {
+        Some(path) => {
+            let definition = std::fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            advent2022::game_runs(&definition).with_context(|| path.display().to_string())
+        }
+        None => Ok(day.runs()),
+    }
+}
+
+/// Run one part inside a `puzzle` span, which logs its elapsed time when
+/// it closes.
+fn run_part(day: PuzzleDay, puzzle: &dyn PuzzleRun, input: &DayInput) -> anyhow::Result<Answer> {
+    let _span = info_span!("puzzle", day = day.number(), part = puzzle.part()).entered();
+    input.run(puzzle)
+}
+
+/// Run `puzzle` and time it, recording the result rather than failing.
+fn run_timed(
+    day: PuzzleDay,
+    puzzle: &dyn PuzzleRun,
+    input: &anyhow::Result<DayInput>,
+    input_name: &str,
+) -> PartResult {
+    let start = Instant::now();
+    let result = match input {
+        Ok(input) => run_part(day, puzzle, input),
+        Err(e) => Err(anyhow::anyhow!("{e:#}")),
+    };
+    PartResult {
+        day: day.number(),
+        part: puzzle.part(),
+        input: input_name.to_string(),
+        elapsed: start.elapsed(),
+        result,
+    }
+}
+
+fn run_day(args: &Args, day: PuzzleDay) -> anyhow::Result<()> {
+    let source = input_source(args, day);
+    let input_name = source.to_string();
+    let input = DayInput::load(source);
+
+    let mut failed = 0;
+    for puzzle in day_runs(args, day)?
+        .into_iter()
+        .filter(|p| args.part.includes(p.part()))
+    {
+        let result = run_timed(day, puzzle.as_ref(), &input, &input_name);
+        match args.format {
+            Format::Text => {
+                let answer = result.result.with_context(|| {
+                    format!(
+                        "day {} part {} ({} input)",
+                        day.number(),
+                        puzzle.part(),
+                        input_name
+                    )
+                })?;
+                println!(
+                    "Part {} ({}): {}",
+                    puzzle.part(),
+                    puzzle.description(),
+                    answer
+                );
+            }
+            Format::Json => {
+                println!("{}", result.to_json());
+                if result.result.is_err() {
+                    failed += 1;
+                }
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{failed} parts of day {} failed", day.number());
+    }
+    Ok(())
+}
+
+/// Run `day` and then again each time its input file changes, showing how
+/// the answers moved.  Errors are printed in place of answers rather than
+/// ending the watch.
+fn watch(args: &Args, day: PuzzleDay) -> anyhow::Result<()> {
+    let source = input_source(args, day);
+    let path = source
+        .path(day.number())
+        .with_context(|| format!("--watch needs an input file to poll, not {source}"))?;
+    let input_name = source.to_string();
+    let mut watcher = watch::Watcher::new(path);
+
+    let mut previous: BTreeMap<u8, String> = BTreeMap::new();
+    loop {
+        let input = DayInput::load(source.clone());
+        for puzzle in day_runs(args, day)?
+            .into_iter()
+            .filter(|p| args.part.includes(p.part()))
+        {
+            let result = run_timed(day, puzzle.as_ref(), &input, &input_name);
+            match args.format {
+                Format::Text => {
+                    let answer = match &result.result {
+                        Ok(answer) => answer.to_string(),
+                        Err(e) => format!("error: {e:#}"),
+                    };
+                    println!(
+                        "Part {} ({}): {}",
+                        puzzle.part(),
+                        puzzle.description(),
+                        watch::diff(previous.get(&puzzle.part()).map(String::as_str), &answer)
+                    );
+                    previous.insert(puzzle.part(), answer);
+                }
+                Format::Json => println!("{}", result.to_json()),
+            }
+        }
+        eprintln!("watching {} for changes", watcher.path().display());
+        watcher.wait();
+    }
+}
+
+fn run_all(args: &Args) -> anyhow::Result<()> {
+    if args.input.is_some() {
+        bail!("running every day can't take --input; use --inputs-dir");
+    }
+    // the runs themselves aren't Send, so each task builds its own
+    let tasks: Vec<(PuzzleDay, u8)> = PuzzleDay::all()
+        .flat_map(|day| day.runs().into_iter().map(move |p| (day, p.part())))
+        .filter(|(_, part)| args.part.includes(*part))
+        .collect();
+
+    let mut results: Vec<PartResult> = tasks
+        .into_par_iter()
+        .map(|(day, part)| {
+            let source = input_source(args, day);
+            let input_name = source.to_string();
+            let puzzle = day
+                .runs()
+                .into_iter()
+                .find(|p| p.part() == part)
+                .expect("part was listed by the same day");
+            run_timed(day, puzzle.as_ref(), &DayInput::load(source), &input_name)
+        })
+        .collect();
+
+    results.sort_by_key(|r| (r.day, r.part));
+    match args.format {
+        Format::Text => summary::print_table(&results),
+        Format::Json => {
+            for r in results.iter() {
+                println!("{}", r.to_json());
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|r| r.result.is_err()).count();
+    if failed > 0 {
+        bail!("{failed} of {} parts failed", results.len());
+    }
+    Ok(())
+}
+
+fn verify(args: &Args, answers_path: &Path, record: bool) -> anyhow::Result<()> {
+    if args.input.is_some() {
+        bail!("verify runs every day, so it can't take --input; use --inputs-dir");
+    }
+    let mut answers = match Answers::load(answers_path) {
+        Err(_) if record && !answers_path.exists() => Answers::default(),
+        answers => answers?,
+    };
+
+    let mut checks = vec![];
+    for day in PuzzleDay::all() {
+        let source = input_source(args, day);
+        let input_name = source.to_string();
+        let input = DayInput::load(source);
+        for puzzle in day
+            .runs()
+            .into_iter()
+            .filter(|p| args.part.includes(p.part()))
+        {
+            let result = match &input {
+                Ok(input) => run_part(day, puzzle.as_ref(), input),
+                Err(e) => Err(anyhow::anyhow!("{e:#}")),
+            };
+            checks.push(Check::new(
+                &answers,
+                day.number(),
+                puzzle.part(),
+                &input_name,
+                &result,
+            ));
         }
+    }
+    verify::print_table(&checks);
+
+    if record {
+        let mut recorded = 0;
+        for check in checks.iter().filter(|c| c.expected.is_none()) {
+            if check.status != verify::Status::Error {
+                answers.insert(check.day, check.part, &check.input, &check.actual);
+                recorded += 1;
+            }
+        }
+        answers.save(answers_path)?;
+        println!(
+            "recorded {recorded} new answers in {}",
+            answers_path.display()
+        );
+    }
+
+    let failed = checks.iter().filter(|c| c.failed()).count();
+    if failed > 0 {
+        bail!("{failed} of {} answers did not verify", checks.len());
+    }
+    Ok(())
+}
+
+fn bench(
+    args: &Args,
+    day: Option<PuzzleDay>,
+    iterations: u32,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    if args.input.is_some() && day.is_none() {
+        bail!("bench without a day runs every day, so it can't take --input; use --inputs-dir");
+    }
+    let output = output
+        .map(|path| bench::OutputFormat::for_path(path).map(|format| (path, format)))
+        .transpose()?;
+    let days: Vec<PuzzleDay> = match day {
+        Some(day) => vec![day],
+        None => PuzzleDay::all().collect(),
     };
 
-    for puzzle in puzzles {
-        println!("{}", puzzle.run(puzzle.input_data()?));
+    let mut results = vec![];
+    for day in days {
+        let source = input_source(args, day);
+        if source == InputSource::Stdin {
+            bail!("bench reads the input on every iteration, so it can't read stdin");
+        }
+        for puzzle in day
+            .runs()
+            .into_iter()
+            .filter(|p| args.part.includes(p.part()))
+        {
+            let context = || {
+                format!(
+                    "day {} part {} ({source} input)",
+                    day.number(),
+                    puzzle.part()
+                )
+            };
+            let mut read_times = vec![];
+            let mut parse_times = vec![];
+            let mut solve_times = vec![];
+            for _ in 0..iterations {
+                let start = Instant::now();
+                let input = DayInput::load(source.clone()).with_context(context)?;
+                let text = input.for_puzzle(puzzle.as_ref()).with_context(context)?;
+                read_times.push(start.elapsed());
+
+                let start = Instant::now();
+                let parsed = puzzle.parse(&text).with_context(context)?;
+                parse_times.push(start.elapsed());
+
+                let start = Instant::now();
+                source
+                    .solve(puzzle.as_ref(), parsed)
+                    .with_context(context)?;
+                solve_times.push(start.elapsed());
+            }
+            results.push(bench::BenchResult {
+                day: day.number(),
+                part: puzzle.part(),
+                input: source.to_string(),
+                iterations,
+                read_time: bench::Timing::from_samples(&mut read_times),
+                parse_time: bench::Timing::from_samples(&mut parse_times),
+                solve_time: bench::Timing::from_samples(&mut solve_times),
+            });
+        }
+    }
+
+    bench::print_table(&results);
+    if let Some((path, format)) = output {
+        bench::write(path, format, &results)?;
+    }
+    Ok(())
+}
+
+fn lint(args: &Args, day: PuzzleDay, file: Option<&Path>) -> anyhow::Result<()> {
+    let source = match file {
+        Some(file) => InputSource::resolve(Some(file), false, None, day.number()),
+        None => input_source(args, day),
+    };
+    let name = source.to_string();
+    let input = DayInput::load(source)?;
+    let runs = day.runs();
+    let first = runs.first().context("day has no parts")?;
+    let text = input.for_puzzle(first.as_ref())?;
+
+    let problems = advent2022::lint::check(day.number(), &text)?;
+    for problem in problems.iter() {
+        println!(
+            "{name}:{}:{}: {}",
+            problem.line, problem.column, problem.msg
+        );
+    }
+    if !problems.is_empty() {
+        bail!("{} problems found in {name}", problems.len());
+    }
+    println!("{name}: ok");
+    Ok(())
+}
+
+fn report(args: &Args, day: PuzzleDay, count: usize) -> anyhow::Result<()> {
+    let source = input_source(args, day);
+    let name = source.to_string();
+    let input = DayInput::load(source)?;
+    let runs = day.runs();
+    let first = runs.first().context("day has no parts")?;
+    let text = input.for_puzzle(first.as_ref())?;
+    let report = advent2022::report(day.number(), &text, count)
+        .with_context(|| format!("day {} ({name} input)", day.number()))?;
+    println!("{report}");
+    Ok(())
+}
+
+fn generate(day: PuzzleDay, scale: usize, seed: u64, output: Option<&Path>) -> anyhow::Result<()> {
+    let text = advent2022::generate::input(day.number(), seed, scale)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, text).with_context(|| format!("writing {}", path.display()))
+        }
+        None => {
+            print!("{text}");
+            Ok(())
+        }
+    }
+}
+
+fn new_day(day: u8) -> anyhow::Result<()> {
+    if advent2022::day(day).is_some() {
+        bail!("day {day} is already registered");
+    }
+    for path in scaffold::new_day(day)? {
+        println!("wrote {}", path.display());
     }
     Ok(())
 }
@@ -0,0 +1,212 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// The module a new day starts from.  It builds, lints and generates
+/// inputs, but both parts return `Unsolved` until they're written.
+const TEMPLATE: &str = r#"use crate::generate::Rng;
+use crate::scanner::{Line, ScanError};
+use crate::{Answer, Parsed, PuzzleRun, Scanner, Unsolved};
+use anyhow::Result;
+use rand::Rng as _;
+use std::fmt::Write;
+
+pub fn get_runs() -> Vec<Box<dyn PuzzleRun>> {
+    vec![Box::new(Part1), Box::new(Part2)]
+}
+
+inventory::submit! {
+    crate::Day {
+        number: {day},
+        runs: get_runs,
+        validate,
+        generate,
+    }
+}
+
+fn parse_line(line: &Line) -> Result<i64, ScanError> {
+    line.parse()
+}
+
+pub(crate) fn validate(input: &str) -> Vec<ScanError> {
+    crate::lint::each_line(input, parse_line)
+}
+
+/// `scale` lines of numbers.
+pub(crate) fn generate(rng: &mut Rng, scale: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..scale.max(1) {
+        writeln!(out, "{}", rng.gen_range(0..1000)).unwrap();
+    }
+    out
+}
+
+fn parse(input: &str) -> Result<Vec<i64>> {
+    Ok(Scanner::new(input)
+        .lines()
+        .map(|line| parse_line(&line))
+        .collect::<Result<_, _>>()?)
+}
+
+struct Part1;
+
+impl PuzzleRun for Part1 {
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn description(&self) -> &str {
+        "part 1"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d{day}/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let _values: Vec<i64> = parsed.take();
+        Err(Unsolved.into())
+    }
+}
+
+struct Part2;
+
+impl PuzzleRun for Part2 {
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "part 2"
+    }
+
+    fn input_data(&self) -> anyhow::Result<String> {
+        crate::read_file("src/d{day}/input.txt")
+    }
+
+    fn example_data(&self) -> Option<&str> {
+        Some(include_str!("example.txt"))
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Parsed> {
+        Ok(Parsed::new(parse(input)?))
+    }
+
+    fn solve(&self, parsed: Parsed) -> anyhow::Result<Answer> {
+        let _values: Vec<i64> = parsed.take();
+        Err(Unsolved.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    example_tests!(
+        #[ignore = "fill in the example and its answers"]
+        {day}: 0, 0
+    );
+}
+"#;
+
+/// Create `src/dN` with a module from the template, an empty example and an
+/// empty input, and generate the module's declaration in `src/lib.rs`; the
+/// day registers itself, so no other file changes.  Run from the root of
+/// the repository.  Returns the files written.
+pub fn new_day(day: u8) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        bail!("day {day} is not in the calendar");
+    }
+    let src = Path::new("src");
+    let lib = src.join("lib.rs");
+    let dir = src.join(format!("d{day}"));
+    if !lib.is_file() {
+        bail!(
+            "no {} here; run new-day from the repository root",
+            lib.display()
+        );
+    }
+    if dir.exists() {
+        bail!("{} already exists", dir.display());
+    }
+
+    let lib_text =
+        std::fs::read_to_string(&lib).with_context(|| format!("reading {}", lib.display()))?;
+    let lib_text = declare_module(&lib_text, day)?;
+
+    std::fs::create_dir(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    let files = [
+        (
+            dir.join("mod.rs"),
+            TEMPLATE.replace("{day}", &day.to_string()),
+        ),
+        (dir.join("example.txt"), String::new()),
+        (dir.join("input.txt"), String::new()),
+        (lib, lib_text),
+    ];
+    for (path, text) in files.iter() {
+        std::fs::write(path, text).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Add `mod dN;` to the block of day modules, which is kept sorted the way
+/// rustfmt sorts it.
+fn declare_module(lib: &str, day: u8) -> Result<String> {
+    let name = |line: &str| {
+        line.strip_prefix("mod ")
+            .and_then(|rest| rest.strip_suffix(';'))
+            .map(str::to_owned)
+    };
+    let is_day = |line: &str| {
+        name(line)
+            .and_then(|name| name.strip_prefix('d')?.parse::<u8>().ok())
+            .is_some()
+    };
+    let mut lines: Vec<&str> = lib.lines().collect();
+    let Some(first) = lines.iter().position(|l| is_day(l)) else {
+        bail!("no day modules declared in src/lib.rs");
+    };
+    let end = first + lines[first..].iter().take_while(|l| is_day(l)).count();
+
+    let declaration = format!("mod d{day};");
+    let mut block: Vec<&str> = lines[first..end].to_vec();
+    if block.contains(&declaration.as_str()) {
+        bail!("src/lib.rs already declares d{day}");
+    }
+    block.push(&declaration);
+    block.sort_by_key(|line| name(line));
+    lines.splice(first..end, block);
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    Ok(text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_declare_module() {
+        let lib = "mod answer;\nmod d1;\nmod d10;\nmod d2;\npub mod geometry;\n";
+        assert_eq!(
+            declare_module(lib, 17).unwrap(),
+            "mod answer;\nmod d1;\nmod d10;\nmod d17;\nmod d2;\npub mod geometry;\n"
+        );
+        assert!(declare_module(lib, 2).is_err());
+    }
+
+    #[test]
+    fn test_template() {
+        let module = TEMPLATE.replace("{day}", "17");
+        assert!(module.contains("number: 17,"));
+        assert!(module.contains("\"src/d17/input.txt\""));
+        assert!(!module.contains("{day}"));
+    }
+}
@@ -0,0 +1,344 @@
+use ndarray::Array2;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A problem with the input, located by 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    pub line: usize,
+    pub column: usize,
+    pub msg: String,
+}
+
+impl ScanError {
+    pub fn new(line: usize, column: usize, msg: impl Display) -> Self {
+        Self {
+            line,
+            column,
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.msg
+        )
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// A piece of a line, remembering where it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Token<'a> {
+    pub fn error(&self, msg: impl Display) -> ScanError {
+        ScanError::new(self.line, self.column, msg)
+    }
+
+    pub fn parse<T>(&self) -> Result<T, ScanError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.text
+            .parse()
+            .map_err(|e| self.error(format!("bad value `{}`: {e}", self.text)))
+    }
+
+    /// The rest of the token after `prefix`, trimmed, if it starts with it.
+    pub fn strip_prefix(&self, prefix: &str) -> Option<Token<'a>> {
+        let rest = self.text.strip_prefix(prefix)?;
+        let start = rest.len() - rest.trim_start().len();
+        Some(Token {
+            line: self.line,
+            column: self.column + prefix.len() + start,
+            text: rest.trim(),
+        })
+    }
+
+    /// Parse a pair such as `498,4` split on `sep`.
+    pub fn pair<T>(&self, sep: &str) -> Result<(T, T), ScanError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match Line::from(*self).split(sep).as_slice() {
+            [a, b] => Ok((a.parse()?, b.parse()?)),
+            _ => Err(self.error(format!("expected a pair separated by `{sep}`"))),
+        }
+    }
+}
+
+/// One line of input with its 1-based line number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    /// Column of the first character of `text`, for slices of a line.
+    column: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Self {
+        Self {
+            number,
+            column: 1,
+            text,
+        }
+    }
+
+    /// An error located at byte `offset` of this line.
+    pub fn error_at(&self, offset: usize, msg: impl Display) -> ScanError {
+        ScanError::new(self.number, self.column + offset, msg)
+    }
+
+    pub fn error(&self, msg: impl Display) -> ScanError {
+        self.error_at(0, msg)
+    }
+
+    /// The whole line, trimmed, as a token.
+    pub fn token(&self) -> Token<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        Token {
+            line: self.number,
+            column: self.column + start,
+            text: self.text.trim(),
+        }
+    }
+
+    pub fn parse<T>(&self) -> Result<T, ScanError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.token().parse()
+    }
+
+    /// The whitespace-separated tokens of the line.
+    pub fn tokens(&self) -> impl Iterator<Item = Token<'a>> + 'a {
+        let (number, column, text) = (self.number, self.column, self.text);
+        text.split_ascii_whitespace().map(move |t| Token {
+            line: number,
+            column: column + (t.as_ptr() as usize - text.as_ptr() as usize),
+            text: t,
+        })
+    }
+
+    /// The fields of the line split on `sep`, each trimmed.
+    pub fn split(&self, sep: &str) -> Vec<Token<'a>> {
+        self.text
+            .split(sep)
+            .map(|field| {
+                let offset = field.as_ptr() as usize - self.text.as_ptr() as usize;
+                Line {
+                    number: self.number,
+                    column: self.column + offset,
+                    text: field,
+                }
+                .token()
+            })
+            .collect()
+    }
+}
+
+/// A token as a line of its own, to split it further.
+impl<'a> From<Token<'a>> for Line<'a> {
+    fn from(token: Token<'a>) -> Self {
+        Line {
+            number: token.line,
+            column: token.column,
+            text: token.text,
+        }
+    }
+}
+
+/// Reads puzzle input as tokens, lines, blank-line-separated groups or
+/// character grids, reporting errors with their line and column.
+pub struct Scanner<'a> {
+    lines: Vec<&'a str>,
+    /// Index of the line being read.
+    line: usize,
+    /// Byte offset into the current line of the next unread token.
+    offset: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().collect(),
+            line: 0,
+            offset: 0,
+        }
+    }
+
+    fn end_error(&self, what: &str) -> ScanError {
+        ScanError::new(
+            self.lines.len() + 1,
+            1,
+            format!("expected {what}, found end of input"),
+        )
+    }
+
+    /// The next whitespace-separated token, which may be on a later line.
+    pub fn next_token(&mut self) -> Option<Token<'a>> {
+        while self.line < self.lines.len() {
+            let text = self.lines[self.line];
+            let rest = &text[self.offset..];
+            let skipped = rest.len() - rest.trim_start().len();
+            let rest = &rest[skipped..];
+            if rest.is_empty() {
+                self.line += 1;
+                self.offset = 0;
+                continue;
+            }
+            let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let start = self.offset + skipped;
+            self.offset = start + len;
+            return Some(Token {
+                line: self.line + 1,
+                column: start + 1,
+                text: &rest[..len],
+            });
+        }
+        None
+    }
+
+    /// Parse the next token as a `T`.
+    pub fn token<T>(&mut self) -> Result<T, ScanError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.next_token()
+            .ok_or_else(|| self.end_error("a value"))?
+            .parse()
+    }
+
+    /// The next unread line.  A partly read line is skipped.
+    pub fn next_line(&mut self) -> Option<Line<'a>> {
+        if self.offset > 0 {
+            self.line += 1;
+            self.offset = 0;
+        }
+        let text = self.lines.get(self.line)?;
+        self.line += 1;
+        Some(Line::new(self.line, text))
+    }
+
+    pub fn lines(&mut self) -> impl Iterator<Item = Line<'a>> + '_ {
+        std::iter::from_fn(move || self.next_line())
+    }
+
+    /// The next run of non-blank lines, skipping any blank lines before it.
+    pub fn next_group(&mut self) -> Option<Vec<Line<'a>>> {
+        let mut group = vec![];
+        while let Some(line) = self.next_line() {
+            if !line.text.trim().is_empty() {
+                group.push(line);
+            } else if !group.is_empty() {
+                break;
+            }
+        }
+        (!group.is_empty()).then_some(group)
+    }
+
+    pub fn groups(&mut self) -> impl Iterator<Item = Vec<Line<'a>>> + '_ {
+        std::iter::from_fn(move || self.next_group())
+    }
+
+    /// Read the next group as a rectangular grid, converting each character
+    /// with `cell`.  A character `cell` rejects is reported as an error.
+    pub fn grid<T, F>(&mut self, mut cell: F) -> Result<Array2<T>, ScanError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = self.next_group().ok_or_else(|| self.end_error("a grid"))?;
+        let width = rows[0].text.chars().count();
+        let mut cells = Vec::with_capacity(rows.len() * width);
+        for row in rows.iter() {
+            let mut n = 0;
+            for (offset, c) in row.text.char_indices() {
+                cells.push(
+                    cell(c).ok_or_else(|| row.error_at(offset, format!("unexpected `{c}`")))?,
+                );
+                n += 1;
+            }
+            if n != width {
+                return Err(row.error(format!("expected {width} columns, found {n}")));
+            }
+        }
+        Ok(Array2::from_shape_vec((rows.len(), width), cells)
+            .expect("every row was checked to have the same width"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        let mut s = Scanner::new("1 2\n\n  x 4");
+        assert_eq!(s.token::<u32>(), Ok(1));
+        assert_eq!(s.token::<u32>(), Ok(2));
+        let err = s.token::<u32>().unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+        assert_eq!(s.token::<u32>(), Ok(4));
+        let err = s.token::<u32>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 4, column 1: expected a value, found end of input"
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        let mut s = Scanner::new("\n1\n2\n\n\n3\n");
+        let groups: Vec<Vec<usize>> = s
+            .groups()
+            .map(|g| g.iter().map(|l| l.number).collect())
+            .collect();
+        assert_eq!(groups, vec![vec![2, 3], vec![6]]);
+    }
+
+    #[test]
+    fn test_split_and_pair() {
+        let line = Line::new(7, "498,4 -> 498,x");
+        let points: Vec<_> = line
+            .split("->")
+            .iter()
+            .map(|t| t.pair::<u32>(","))
+            .collect();
+        assert_eq!(points[0], Ok((498, 4)));
+        let err = points[1].clone().unwrap_err();
+        assert_eq!((err.line, err.column), (7, 14));
+        assert!(Line::new(1, "1,2,3").token().pair::<u32>(",").is_err());
+
+        let rest = Line::new(2, "  Test: divisible by 23").token();
+        let rest = rest.strip_prefix("Test: divisible by").unwrap();
+        assert_eq!((rest.column, rest.text), (22, "23"));
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = Scanner::new("12\n34\n\nrest")
+            .grid(|c| c.to_digit(10))
+            .unwrap();
+        assert_eq!(grid, ndarray::arr2(&[[1, 2], [3, 4]]));
+
+        let err = Scanner::new("12\n3x").grid(|c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = Scanner::new("12\n3").grid(|c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.msg, "expected 2 columns, found 1");
+    }
+}
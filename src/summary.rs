@@ -0,0 +1,91 @@
+use advent2022::Answer;
+use serde::Serialize;
+use std::time::Duration;
+
+/// What running one part produced.
+#[derive(Debug)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    /// Where the input came from, as shown by `InputSource`.
+    pub input: String,
+    pub elapsed: Duration,
+    pub result: anyhow::Result<Answer>,
+}
+
+/// The JSON form of a `PartResult`.  Exactly one of `answer` and `error`
+/// is set.
+#[derive(Serialize)]
+struct Record<'a> {
+    day: u8,
+    part: u8,
+    input: &'a str,
+    answer: Option<&'a Answer>,
+    elapsed_ns: u64,
+    error: Option<String>,
+}
+
+impl PartResult {
+    /// One line of JSON describing this result.
+    pub fn to_json(&self) -> String {
+        let record = Record {
+            day: self.day,
+            part: self.part,
+            input: &self.input,
+            answer: self.result.as_ref().ok(),
+            elapsed_ns: self.elapsed.as_nanos() as u64,
+            error: self.result.as_ref().err().map(|e| format!("{e:#}")),
+        };
+        serde_json::to_string(&record).expect("records always serialize")
+    }
+}
+
+/// Print `results` as a table.  Grid answers continue on the following
+/// lines, lined up under the answer column.
+pub fn print_table(results: &[PartResult]) {
+    println!("{:>3} {:>4} {:>10}  answer", "day", "part", "elapsed");
+    let indent = " ".repeat(3 + 1 + 4 + 1 + 10 + 2);
+    for r in results.iter() {
+        let answer = match &r.result {
+            Ok(answer) => answer.to_string(),
+            Err(e) => format!("error: {e:#}"),
+        };
+        let mut lines = answer.lines();
+        println!(
+            "{:>3} {:>4} {:>10}  {}",
+            r.day,
+            r.part,
+            format!("{:.2?}", r.elapsed),
+            lines.next().unwrap_or_default()
+        );
+        for line in lines {
+            println!("{indent}{line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let mut r = PartResult {
+            day: 10,
+            part: 2,
+            input: "default".to_string(),
+            elapsed: Duration::from_micros(3),
+            result: Ok(Answer::grid("#.\n.#")),
+        };
+        assert_eq!(
+            r.to_json(),
+            r##"{"day":10,"part":2,"input":"default","answer":["#.",".#"],"elapsed_ns":3000,"error":null}"##
+        );
+
+        r.result = Err(anyhow::anyhow!("bad input").context("line 3"));
+        assert_eq!(
+            r.to_json(),
+            r#"{"day":10,"part":2,"input":"default","answer":null,"elapsed_ns":3000,"error":"line 3: bad input"}"#
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use advent2022::Answer;
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Answers recorded for regression checks, keyed by day, part and input name.
+///
+/// The file holds one answer per line as `day part input answer`, where the
+/// answer is the rest of the line with newlines written as `\n`.  Blank lines
+/// and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: BTreeMap<(u8, u8, String), String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading answers from {}", path.display()))?;
+        text.parse()
+            .with_context(|| format!("parsing answers in {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut text = String::from("# day part input answer\n");
+        for ((day, part, input), answer) in self.entries.iter() {
+            text.push_str(&format!("{day} {part} {input} {}\n", escape(answer)));
+        }
+        std::fs::write(path, text).with_context(|| format!("writing {}", path.display()))
+    }
+
+    pub fn get(&self, day: u8, part: u8, input: &str) -> Option<&str> {
+        self.entries
+            .get(&(day, part, input.to_string()))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, input: &str, answer: &str) {
+        self.entries
+            .insert((day, part, input.to_string()), answer.to_string());
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut answers = Answers::default();
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(4, char::is_whitespace);
+            let (Some(day), Some(part), Some(input), Some(answer)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                bail!("line {}: expected `day part input answer`", n + 1);
+            };
+            let day = day
+                .parse()
+                .with_context(|| format!("line {}: bad day {day}", n + 1))?;
+            let part = part
+                .parse()
+                .with_context(|| format!("line {}: bad part {part}", n + 1))?;
+            answers.insert(day, part, input, &unescape(answer.trim()));
+        }
+        Ok(answers)
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut out = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Error,
+    Unrecorded,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // pad here; width from the caller's format spec isn't applied by write!
+        let s = match self {
+            Status::Pass => "pass",
+            Status::Fail => "FAIL",
+            Status::Error => "ERROR",
+            Status::Unrecorded => "unrecorded",
+        };
+        f.pad(s)
+    }
+}
+
+/// The outcome of checking one part against the recorded answers.
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub status: Status,
+    /// The answer produced, or the error message if the run failed.
+    pub actual: String,
+    pub expected: Option<String>,
+}
+
+impl Check {
+    pub fn new(answers: &Answers, day: u8, part: u8, input: &str, result: &Result<Answer>) -> Self {
+        let expected = answers.get(day, part, input).map(str::to_string);
+        let (status, actual) = match (result, &expected) {
+            (Err(e), _) => (Status::Error, format!("{e:#}")),
+            (Ok(a), None) => (Status::Unrecorded, a.to_string()),
+            (Ok(a), Some(x)) if a.to_string() == *x => (Status::Pass, a.to_string()),
+            (Ok(a), Some(_)) => (Status::Fail, a.to_string()),
+        };
+        Self {
+            day,
+            part,
+            input: input.to_string(),
+            status,
+            actual,
+            expected,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Fail | Status::Error)
+    }
+}
+
+pub fn print_table(checks: &[Check]) {
+    let input_width = checks
+        .iter()
+        .map(|c| c.input.len())
+        .max()
+        .unwrap_or(0)
+        .max("input".len());
+    println!(
+        "{:>3} {:>4} {:input_width$} {:10} answer",
+        "day", "part", "input", "status"
+    );
+    for c in checks {
+        let mut answer = escape(&c.actual);
+        if c.status == Status::Fail {
+            if let Some(expected) = &c.expected {
+                answer = format!("{answer} (expected {})", escape(expected));
+            }
+        }
+        println!(
+            "{:>3} {:>4} {:input_width$} {:10} {}",
+            c.day, c.part, c.input, c.status, answer
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers: Answers = "# day part input answer
+            1 1 default 67027
+
+            10 2 example ##..\\n#..#
+            3 2 default a b  c"
+            .parse()
+            .unwrap();
+        assert_eq!(answers.get(1, 1, "default"), Some("67027"));
+        assert_eq!(answers.get(10, 2, "example"), Some("##..\n#..#"));
+        assert_eq!(answers.get(3, 2, "default"), Some("a b  c"));
+        assert_eq!(answers.get(1, 2, "default"), None);
+
+        assert!("1 x default 3".parse::<Answers>().is_err());
+        assert!("1 1 default".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        let s = "a\\nb\nc\\";
+        assert_eq!(unescape(&escape(s)), s);
+    }
+
+    #[test]
+    fn test_check_status() {
+        let answers: Answers = "1 1 default 5".parse().unwrap();
+        let check = |part, result| Check::new(&answers, 1, part, "default", &result).status;
+        assert_eq!(check(1, Ok(5.into())), Status::Pass);
+        assert_eq!(check(1, Ok(6.into())), Status::Fail);
+        assert_eq!(check(2, Ok(6.into())), Status::Unrecorded);
+        assert_eq!(check(1, Err(anyhow::anyhow!("boom"))), Status::Error);
+    }
+}
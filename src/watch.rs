@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How often the watched file is polled.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A file's modification time and length, compared between polls.  A
+/// missing file has no stamp, so deleting and recreating it counts as a
+/// change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl Stamp {
+    fn of(path: &Path) -> Option<Self> {
+        let meta = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: meta.modified().ok()?,
+            len: meta.len(),
+        })
+    }
+}
+
+/// Polls one file for changes.
+pub struct Watcher {
+    path: PathBuf,
+    stamp: Option<Stamp>,
+}
+
+impl Watcher {
+    pub fn new(path: PathBuf) -> Self {
+        let stamp = Stamp::of(&path);
+        Self { path, stamp }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the file has changed since it was last looked at.
+    pub fn poll(&mut self) -> bool {
+        let stamp = Stamp::of(&self.path);
+        if stamp == self.stamp {
+            return false;
+        }
+        self.stamp = stamp;
+        true
+    }
+
+    /// Block until the file changes.
+    pub fn wait(&mut self) {
+        while !self.poll() {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Show `current` against the answer from the previous run.  Single-line
+/// answers note what they were; grids are compared row by row, with
+/// changed rows marked `-` for the old and `+` for the new.
+pub fn diff(previous: Option<&str>, current: &str) -> String {
+    let Some(previous) = previous else {
+        return current.to_string();
+    };
+    let multiline = previous.contains('\n') || current.contains('\n');
+    match (previous == current, multiline) {
+        (true, false) => format!("{current} (unchanged)"),
+        (false, false) => format!("{current} (was {previous})"),
+        (true, true) => format!("(unchanged)\n{current}"),
+        (false, true) => {
+            let mut out = String::from("(changed)");
+            let mut old = previous.lines();
+            let mut new = current.lines();
+            loop {
+                match (old.next(), new.next()) {
+                    (None, None) => break,
+                    (Some(a), Some(b)) if a == b => out.push_str(&format!("\n  {b}")),
+                    (a, b) => {
+                        if let Some(a) = a {
+                            out.push_str(&format!("\n- {a}"));
+                        }
+                        if let Some(b) = b {
+                            out.push_str(&format!("\n+ {b}"));
+                        }
+                    }
+                }
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff(None, "24"), "24");
+        assert_eq!(diff(Some("24"), "24"), "24 (unchanged)");
+        assert_eq!(diff(Some("23"), "24"), "24 (was 23)");
+        assert_eq!(diff(Some("#.\n.#"), "#.\n.#"), "(unchanged)\n#.\n.#");
+        assert_eq!(
+            diff(Some("#.\n.#"), "#.\n##\n.."),
+            "(changed)\n  #.\n- .#\n+ ##\n+ .."
+        );
+    }
+
+    #[test]
+    fn test_poll() {
+        let path = std::env::temp_dir().join(format!("advent2022-watch-{}", std::process::id()));
+        std::fs::write(&path, "1").unwrap();
+        let mut watcher = Watcher::new(path.clone());
+        assert!(!watcher.poll());
+
+        std::fs::write(&path, "12").unwrap();
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+    }
+}